@@ -11,7 +11,7 @@ use serde::{
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use wasm_bindgen::prelude::*;
 
-use super::{macros::named_enum_serialize_deserialize, InvalidConstantString};
+use super::{macros::named_enum_serialize_deserialize, InvalidConstantString, Part};
 use crate::{JsCollectionFromValue, JsCollectionIntoValue};
 
 /// Translates `STRUCTURE_*` constants.
@@ -653,6 +653,25 @@ pub enum Boost {
     Tough(f32),
 }
 
+impl Boost {
+    /// The body part type which this boost applies to.
+    #[inline]
+    pub const fn part(self) -> Part {
+        match self {
+            Boost::Harvest(_)
+            | Boost::BuildAndRepair(_)
+            | Boost::Dismantle(_)
+            | Boost::UpgradeController(_) => Part::Work,
+            Boost::Attack(_) => Part::Attack,
+            Boost::RangedAttack(_) => Part::RangedAttack,
+            Boost::Heal(_) => Part::Heal,
+            Boost::Carry(_) => Part::Carry,
+            Boost::Move(_) => Part::Move,
+            Boost::Tough(_) => Part::Tough,
+        }
+    }
+}
+
 /// Translates all resource types that can be used on the market.
 #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize, Sequence)]
 #[serde(untagged)]
@@ -256,9 +256,15 @@ where
 
         FindRouteOptions { route_callback }
     }
+
+    /// Gets the cost of entering a room from a neighboring room using the
+    /// route callback.
+    pub(crate) fn room_cost(&mut self, to_room: RoomName, from_room: RoomName) -> f64 {
+        (self.route_callback)(to_room, from_room)
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub struct RouteStep {
     pub exit: ExitDirection,
     pub room: RoomName,
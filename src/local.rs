@@ -1,14 +1,27 @@
 //! Pure-data structures relating to Screeps.
 use std::ops::Range;
 
+mod area;
+mod body;
 mod cost_matrix;
+mod direction_path;
+mod distance_transform;
+mod line;
 mod lodash_filter;
+mod min_cut;
 mod object_id;
+mod pathfinder;
 mod position;
+mod regions;
 mod room_coordinate;
+mod room_map;
 mod room_name;
 mod room_xy;
+mod room_xy_set;
+mod route;
+mod serialized_path;
 mod terrain;
+mod tower;
 
 /// Represents two constants related to room names.
 ///
@@ -32,6 +45,8 @@ const VALID_WORLD_POSITIONS: Range<i32> =
 use crate::ROOM_SIZE;
 
 pub use self::{
-    cost_matrix::*, lodash_filter::*, object_id::*, position::*, room_coordinate::*, room_name::*,
-    room_xy::*, terrain::*,
+    body::*, cost_matrix::*, direction_path::*, distance_transform::*, line::*, lodash_filter::*,
+    min_cut::*, object_id::*, pathfinder::*, position::*, regions::*, room_coordinate::*,
+    room_map::*, room_name::*, room_xy::*, room_xy_set::*, route::*, serialized_path::*,
+    terrain::*, tower::*,
 };
@@ -0,0 +1,294 @@
+//! Iterators over the tiles in an area around a position, which don't
+//! allocate.
+use crate::constants::{Direction, ROOM_SIZE};
+
+use super::{Position, RoomXY, VALID_WORLD_POSITIONS};
+
+/// Iterates over the offsets at exactly a linear range, clockwise starting
+/// from the top left corner.
+pub(super) fn ring_offsets(range: i32) -> impl Iterator<Item = (i32, i32)> {
+    let len = if range == 0 { 1 } else { range * 8 };
+    (0..len).map(move |idx| {
+        if range == 0 {
+            return (0, 0);
+        }
+        let side_len = range * 2;
+        let along = idx % side_len;
+        match idx / side_len {
+            0 => (-range + along, -range),
+            1 => (range, -range + along),
+            2 => (range - along, range),
+            _ => (-range, range - along),
+        }
+    })
+}
+
+/// Iterates over the offsets within a linear range, row by row.
+fn square_offsets(range: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-range..=range).flat_map(move |y| (-range..=range).map(move |x| (x, y)))
+}
+
+/// Whether an offset is within a euclidean radius.
+fn in_circle((x, y): (i32, i32), radius: i32) -> bool {
+    x * x + y * y <= radius * radius
+}
+
+impl RoomXY {
+    /// Iterates over the tiles within the given range of this position,
+    /// including this position, clipped to the room's bounds.
+    ///
+    /// Range is measured the same way as [`RoomXY::get_range_to`], so the
+    /// tiles form a square. They're iterated over row by row, in the same
+    /// order as [`RoomXY`]'s [`Ord`] implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomXY;
+    ///
+    /// let corner = RoomXY::checked_new(0, 0).unwrap();
+    /// assert_eq!(corner.tiles_in_range(1).count(), 4);
+    ///
+    /// let center = RoomXY::checked_new(25, 25).unwrap();
+    /// assert_eq!(center.tiles_in_range(3).count(), 49);
+    /// ```
+    pub fn tiles_in_range(self, range: u8) -> impl Iterator<Item = RoomXY> {
+        let clamp = |coord: u8| {
+            (
+                coord.saturating_sub(range),
+                coord.saturating_add(range).min(ROOM_SIZE - 1),
+            )
+        };
+        let (min_x, max_x) = clamp(self.x.u8());
+        let (min_y, max_y) = clamp(self.y.u8());
+
+        (min_y..=max_y).flat_map(move |y| {
+            (min_x..=max_x).map(move |x| {
+                // SAFETY: both coordinates are clamped to the room's bounds.
+                unsafe { RoomXY::unchecked_new(x, y) }
+            })
+        })
+    }
+
+    /// Iterates over the tiles at exactly the given range of this position,
+    /// clipped to the room's bounds.
+    ///
+    /// The tiles form the edge of a square, and are iterated over clockwise
+    /// starting from its top left corner. A range of 0 yields only this
+    /// position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomXY;
+    ///
+    /// let xy = RoomXY::checked_new(10, 48).unwrap();
+    /// let ring: Vec<_> = xy.ring_at_range(3).collect();
+    ///
+    /// // the bottom edge of the square is outside of the room
+    /// assert_eq!(ring.len(), 7 + 4 + 4);
+    /// assert!(ring.iter().all(|tile| tile.get_range_to(xy) == 3));
+    /// ```
+    pub fn ring_at_range(self, range: u8) -> impl Iterator<Item = RoomXY> {
+        // no tile is further away than the size of the room
+        let range = range.min(ROOM_SIZE) as i32;
+        ring_offsets(range).filter_map(move |(x, y)| self.checked_add((x as i8, y as i8)))
+    }
+
+    /// Iterates over the tiles within the given euclidean distance of this
+    /// position, including this position, clipped to the room's bounds.
+    ///
+    /// The tiles form a circle, and are iterated over row by row like
+    /// [`RoomXY::tiles_in_range`].
+    pub fn tiles_in_circle(self, radius: u8) -> impl Iterator<Item = RoomXY> {
+        self.tiles_in_range(radius).filter(move |&tile| {
+            let (x, y) = tile - self;
+            in_circle((x as i32, y as i32), radius as i32)
+        })
+    }
+
+    /// Iterates over the adjacent tiles of this position which are within the
+    /// room, in the order of [`Direction::iter`].
+    ///
+    /// This is like [`RoomXY::neighbors`], but doesn't allocate.
+    pub fn neighbors_iter(self) -> impl Iterator<Item = RoomXY> {
+        Direction::iter().filter_map(move |dir| self.checked_add_direction(*dir))
+    }
+}
+
+impl Position {
+    /// Iterates over the positions within the given range of this position,
+    /// including this position.
+    ///
+    /// If `cross_rooms` is `true`, this includes positions in neighboring
+    /// rooms, measuring range in world coordinates; otherwise positions are
+    /// clipped to the bounds of this position's room. Positions are iterated
+    /// over row by row, like [`RoomXY::tiles_in_range`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::{Position, RoomCoordinate, RoomName};
+    ///
+    /// let room: RoomName = "W1N1".parse().unwrap();
+    /// let pos = Position::new(
+    ///     RoomCoordinate::new(49).unwrap(),
+    ///     RoomCoordinate::new(25).unwrap(),
+    ///     room,
+    /// );
+    ///
+    /// assert_eq!(pos.tiles_in_range(1, false).count(), 6);
+    ///
+    /// let crossing: Vec<_> = pos.tiles_in_range(1, true).collect();
+    /// assert_eq!(crossing.len(), 9);
+    /// assert_eq!(
+    ///     crossing.iter().filter(|pos| pos.room_name() != room).count(),
+    ///     3
+    /// );
+    /// ```
+    pub fn tiles_in_range(self, range: u32, cross_rooms: bool) -> impl Iterator<Item = Position> {
+        let range = self.clamp_area_range(range, cross_rooms);
+        self.area_positions(square_offsets(range), cross_rooms)
+    }
+
+    /// Iterates over the positions at exactly the given range of this
+    /// position.
+    ///
+    /// If `cross_rooms` is `true`, this includes positions in neighboring
+    /// rooms, measuring range in world coordinates; otherwise positions are
+    /// clipped to the bounds of this position's room. Positions are iterated
+    /// over clockwise like [`RoomXY::ring_at_range`].
+    pub fn ring_at_range(self, range: u32, cross_rooms: bool) -> impl Iterator<Item = Position> {
+        let range = self.clamp_area_range(range, cross_rooms);
+        self.area_positions(ring_offsets(range), cross_rooms)
+    }
+
+    /// Iterates over the positions within the given euclidean distance of
+    /// this position, including this position.
+    ///
+    /// If `cross_rooms` is `true`, this includes positions in neighboring
+    /// rooms, measuring distance in world coordinates; otherwise positions
+    /// are clipped to the bounds of this position's room.
+    pub fn tiles_in_circle(self, radius: u32, cross_rooms: bool) -> impl Iterator<Item = Position> {
+        let radius = self.clamp_area_range(radius, cross_rooms);
+        let offsets = square_offsets(radius).filter(move |&offset| in_circle(offset, radius));
+        self.area_positions(offsets, cross_rooms)
+    }
+
+    /// Iterates over the adjacent positions of this position, in the order of
+    /// [`Direction::iter`].
+    ///
+    /// If `cross_rooms` is `true`, this includes positions in neighboring
+    /// rooms; otherwise only positions in this position's room are included.
+    pub fn neighbors_iter(self, cross_rooms: bool) -> impl Iterator<Item = Position> {
+        self.area_positions(Direction::iter().map(|&dir| dir.into()), cross_rooms)
+    }
+
+    fn clamp_area_range(self, range: u32, cross_rooms: bool) -> i32 {
+        let max = if cross_rooms {
+            VALID_WORLD_POSITIONS.len() as u32
+        } else {
+            ROOM_SIZE as u32
+        };
+        range.min(max) as i32
+    }
+
+    fn area_positions(
+        self,
+        offsets: impl Iterator<Item = (i32, i32)>,
+        cross_rooms: bool,
+    ) -> impl Iterator<Item = Position> {
+        let room_name = self.room_name();
+        offsets
+            .filter_map(move |offset| self.checked_add(offset).ok())
+            .filter(move |pos| cross_rooms || pos.room_name() == room_name)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::{RoomCoordinate, RoomName};
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn room_xy_areas() {
+        for center in [xy(0, 0), xy(1, 48), xy(25, 25), xy(49, 10)] {
+            for range in [0, 1, 3, 10, 49, u8::MAX] {
+                let in_range: Vec<_> = center.tiles_in_range(range).collect();
+                let expected: Vec<_> = (0..50)
+                    .flat_map(|y| (0..50).map(move |x| xy(x, y)))
+                    .filter(|tile| tile.get_range_to(center) <= range)
+                    .collect();
+                assert_eq!(in_range, expected);
+
+                let mut ring: Vec<_> = center.ring_at_range(range).collect();
+                ring.sort();
+                let expected: Vec<_> = expected
+                    .into_iter()
+                    .filter(|tile| tile.get_range_to(center) == range)
+                    .collect();
+                assert_eq!(ring, expected);
+            }
+
+            let neighbors: Vec<_> = center.neighbors_iter().collect();
+            assert_eq!(neighbors, center.neighbors());
+        }
+
+        let circle: Vec<_> = xy(25, 25).tiles_in_circle(2).collect();
+        assert_eq!(circle.len(), 13);
+        assert!(!circle.contains(&xy(27, 27)));
+        assert!(circle.contains(&xy(27, 25)));
+    }
+
+    #[test]
+    fn ring_is_clockwise() {
+        let ring: Vec<(u8, u8)> = xy(5, 5).ring_at_range(1).map(Into::into).collect();
+        assert_eq!(
+            ring,
+            [
+                (4, 4),
+                (5, 4),
+                (6, 4),
+                (6, 5),
+                (6, 6),
+                (5, 6),
+                (4, 6),
+                (4, 5)
+            ]
+        );
+    }
+
+    #[test]
+    fn position_areas() {
+        let coord = |value| RoomCoordinate::new(value).unwrap();
+        let room: RoomName = "E0S0".parse().unwrap();
+        let corner = Position::new(coord(0), coord(0), room);
+
+        assert_eq!(corner.tiles_in_range(2, false).count(), 9);
+        assert_eq!(corner.tiles_in_range(2, true).count(), 25);
+        assert_eq!(corner.ring_at_range(2, false).count(), 5);
+        assert_eq!(corner.ring_at_range(2, true).count(), 16);
+        assert!(corner
+            .ring_at_range(2, true)
+            .all(|pos| pos.get_range_to(corner) == 2));
+        assert_eq!(corner.tiles_in_circle(2, true).count(), 13);
+        assert_eq!(corner.neighbors_iter(false).count(), 3);
+
+        let rooms: Vec<_> = corner
+            .neighbors_iter(true)
+            .map(|pos| pos.room_name())
+            .collect();
+        assert_eq!(rooms.len(), 8);
+        for name in ["W0N0", "E0N0", "W0S0"] {
+            assert!(rooms.contains(&name.parse().unwrap()));
+        }
+
+        // positions past the edge of the world are skipped
+        let world_corner = Position::new(coord(0), coord(0), "W127N127".parse().unwrap());
+        assert_eq!(world_corner.tiles_in_range(1, true).count(), 4);
+    }
+}
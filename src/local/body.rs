@@ -0,0 +1,453 @@
+//! Creep bodies, built and analyzed in Rust memory.
+use std::{error::Error, fmt};
+
+use crate::constants::{Part, CREEP_SPAWN_TIME, MAX_CREEP_SIZE};
+
+mod damage;
+mod movement;
+mod stats;
+
+pub use self::{damage::*, movement::*, stats::*};
+
+/// A list of body parts which is valid for spawning a creep: not empty, and
+/// with at most [`MAX_CREEP_SIZE`] parts.
+///
+/// Use [`BodyBuilder`] to build a body from a repeated pattern of parts
+/// within an energy budget.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{constants::Part, local::BodySpec};
+///
+/// let body = BodySpec::new(vec![Part::Work, Part::Carry, Part::Move]).unwrap();
+/// assert_eq!(body.cost(), 200);
+/// assert_eq!(body.spawn_time(), 9);
+///
+/// // spawn.spawn_creep(body.parts(), "worker")
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BodySpec {
+    parts: Vec<Part>,
+}
+
+impl BodySpec {
+    /// Creates a body from a list of parts, checking that it can be spawned.
+    pub fn new(parts: Vec<Part>) -> Result<Self, BodyError> {
+        if parts.is_empty() {
+            return Err(BodyError::Empty);
+        }
+        if parts.len() > MAX_CREEP_SIZE as usize {
+            return Err(BodyError::TooManyParts { len: parts.len() });
+        }
+        Ok(BodySpec { parts })
+    }
+
+    /// Gets the parts of the body, in order, as passed to
+    /// [`StructureSpawn::spawn_creep`].
+    ///
+    /// [`StructureSpawn::spawn_creep`]: crate::objects::StructureSpawn::spawn_creep
+    #[inline]
+    pub fn parts(&self) -> &[Part] {
+        &self.parts
+    }
+
+    /// Gets the number of parts in the body.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Always `false`, as bodies can't be empty; provided for consistency
+    /// with [`BodySpec::len`].
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// Gets the number of parts of the given type in the body.
+    pub fn count(&self, part: Part) -> usize {
+        self.parts.iter().filter(|&&p| p == part).count()
+    }
+
+    /// Gets the energy cost of spawning the body.
+    pub fn cost(&self) -> u32 {
+        body_cost(&self.parts)
+    }
+
+    /// Gets the number of ticks spawning the body takes, without any power
+    /// effects on the spawn.
+    pub fn spawn_time(&self) -> u32 {
+        self.parts.len() as u32 * CREEP_SPAWN_TIME
+    }
+}
+
+impl AsRef<[Part]> for BodySpec {
+    fn as_ref(&self) -> &[Part] {
+        &self.parts
+    }
+}
+
+impl From<BodySpec> for Vec<Part> {
+    fn from(body: BodySpec) -> Vec<Part> {
+        body.parts
+    }
+}
+
+impl TryFrom<Vec<Part>> for BodySpec {
+    type Error = BodyError;
+
+    fn try_from(parts: Vec<Part>) -> Result<Self, BodyError> {
+        BodySpec::new(parts)
+    }
+}
+
+fn body_cost(parts: &[Part]) -> u32 {
+    parts.iter().map(|part| part.cost()).sum()
+}
+
+/// The order to place the parts of a body built by a [`BodyBuilder`] in.
+///
+/// Parts earlier in a body take damage first, so the order affects how long
+/// a creep keeps its important parts when attacked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum BodyOrder {
+    /// Keep the parts in the order they were added: the prefix, each
+    /// repetition of the pattern, then the suffix.
+    #[default]
+    AsBuilt,
+    /// Move all [`Part::Tough`] parts to the start and all [`Part::Move`]
+    /// parts to the end, keeping the order of the other parts.
+    ToughFirstMoveLast,
+    /// Group parts of the same type together, in the order [`Part::Tough`],
+    /// [`Part::Work`], [`Part::Carry`], [`Part::Claim`], [`Part::Attack`],
+    /// [`Part::RangedAttack`], [`Part::Heal`], [`Part::Move`].
+    Grouped,
+}
+
+impl BodyOrder {
+    fn apply(self, parts: &mut [Part]) {
+        // sort_by_key is stable, so parts with the same key keep their order
+        match self {
+            BodyOrder::AsBuilt => {}
+            BodyOrder::ToughFirstMoveLast => parts.sort_by_key(|part| match part {
+                Part::Tough => 0,
+                Part::Move => 2,
+                _ => 1,
+            }),
+            BodyOrder::Grouped => parts.sort_by_key(|part| match part {
+                Part::Tough => 0,
+                Part::Work => 1,
+                Part::Carry => 2,
+                Part::Claim => 3,
+                Part::Attack => 4,
+                Part::RangedAttack => 5,
+                Part::Heal => 6,
+                _ => 7,
+            }),
+        }
+    }
+}
+
+/// Builds a [`BodySpec`] from a repeated pattern of parts, fitting within an
+/// energy budget and [`MAX_CREEP_SIZE`].
+///
+/// The body is made of the prefix, the pattern repeated as many times as fit,
+/// and the suffix, then put in the chosen [`BodyOrder`].
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::Part,
+///     local::{BodyBuilder, BodyOrder},
+/// };
+///
+/// let body = BodyBuilder::new()
+///     .pattern(&[Part::Work, Part::Carry, Part::Move])
+///     .max_energy(800)
+///     .order(BodyOrder::Grouped)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(body.count(Part::Work), 4);
+/// assert_eq!(body.cost(), 800);
+/// assert_eq!(body.parts()[0], Part::Work);
+/// assert_eq!(body.parts()[11], Part::Move);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct BodyBuilder {
+    prefix: Vec<Part>,
+    pattern: Vec<Part>,
+    suffix: Vec<Part>,
+    max_energy: Option<u32>,
+    max_repeats: Option<u32>,
+    order: BodyOrder,
+}
+
+impl BodyBuilder {
+    /// Creates a builder with no parts.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets parts to add once, before the repeated pattern.
+    pub fn prefix(mut self, parts: &[Part]) -> Self {
+        self.prefix = parts.to_vec();
+        self
+    }
+
+    /// Sets the pattern of parts to repeat as many times as fit.
+    ///
+    /// If a pattern is set, it must fit at least once.
+    pub fn pattern(mut self, parts: &[Part]) -> Self {
+        self.pattern = parts.to_vec();
+        self
+    }
+
+    /// Sets parts to add once, after the repeated pattern.
+    pub fn suffix(mut self, parts: &[Part]) -> Self {
+        self.suffix = parts.to_vec();
+        self
+    }
+
+    /// Sets the most energy the body may cost, such as the spawning room's
+    /// energy capacity. Without this, the pattern is only limited by
+    /// [`MAX_CREEP_SIZE`] and [`BodyBuilder::max_repeats`].
+    pub fn max_energy(mut self, energy: u32) -> Self {
+        self.max_energy = Some(energy);
+        self
+    }
+
+    /// Sets the most times the pattern may be repeated.
+    pub fn max_repeats(mut self, repeats: u32) -> Self {
+        self.max_repeats = Some(repeats);
+        self
+    }
+
+    /// Sets the order to place the parts of the body in.
+    pub fn order(mut self, order: BodyOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Builds the body, repeating the pattern as many times as fit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body would be empty, if the prefix and suffix
+    /// along with one repetition of the pattern have more than
+    /// [`MAX_CREEP_SIZE`] parts, or if they cost more than the maximum
+    /// energy.
+    pub fn build(&self) -> Result<BodySpec, BodyError> {
+        let fixed_len = self.prefix.len() + self.suffix.len();
+        let fixed_cost = body_cost(&self.prefix) + body_cost(&self.suffix);
+
+        let mut repeats = 0;
+        if !self.pattern.is_empty() {
+            let pattern_cost = body_cost(&self.pattern);
+
+            let mut fit = (MAX_CREEP_SIZE as usize).saturating_sub(fixed_len) / self.pattern.len();
+            if let Some(max_energy) = self.max_energy {
+                let energy_fit = match pattern_cost {
+                    0 => usize::MAX,
+                    cost => (max_energy.saturating_sub(fixed_cost) / cost) as usize,
+                };
+                fit = fit.min(energy_fit);
+            }
+            if let Some(max_repeats) = self.max_repeats {
+                fit = fit.min(max_repeats as usize);
+            }
+
+            if fit == 0 && self.max_repeats != Some(0) {
+                // report why even one repetition doesn't fit
+                let len = fixed_len + self.pattern.len();
+                if len > MAX_CREEP_SIZE as usize {
+                    return Err(BodyError::TooManyParts { len });
+                }
+                return Err(BodyError::TooExpensive {
+                    cost: fixed_cost + pattern_cost,
+                    max_energy: self.max_energy.unwrap_or_default(),
+                });
+            }
+            repeats = fit;
+        }
+
+        if let Some(max_energy) = self.max_energy {
+            if fixed_cost > max_energy {
+                return Err(BodyError::TooExpensive {
+                    cost: fixed_cost,
+                    max_energy,
+                });
+            }
+        }
+
+        let mut parts = Vec::with_capacity(fixed_len + self.pattern.len() * repeats);
+        parts.extend_from_slice(&self.prefix);
+        for _ in 0..repeats {
+            parts.extend_from_slice(&self.pattern);
+        }
+        parts.extend_from_slice(&self.suffix);
+        self.order.apply(&mut parts);
+
+        BodySpec::new(parts)
+    }
+}
+
+/// An error representing when a body can't be spawned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BodyError {
+    /// The body has no parts.
+    Empty,
+    /// The body has more than [`MAX_CREEP_SIZE`] parts.
+    TooManyParts { len: usize },
+    /// The body costs more than the maximum energy allowed.
+    TooExpensive { cost: u32, max_energy: u32 },
+}
+
+impl Error for BodyError {}
+
+impl fmt::Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Empty => write!(f, "body has no parts"),
+            BodyError::TooManyParts { len } => write!(
+                f,
+                "body has {len} parts, more than the maximum of {MAX_CREEP_SIZE}"
+            ),
+            BodyError::TooExpensive { cost, max_energy } => write!(
+                f,
+                "body costs {cost} energy, more than the maximum of {max_energy}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use Part::*;
+
+    #[test]
+    fn spec_validation() {
+        assert_eq!(BodySpec::new(vec![]), Err(BodyError::Empty));
+        assert_eq!(
+            BodySpec::new(vec![Move; 51]),
+            Err(BodyError::TooManyParts { len: 51 })
+        );
+
+        let body = BodySpec::new(vec![Tough, Attack, Heal, Claim, Move]).unwrap();
+        assert_eq!(body.cost(), 10 + 80 + 250 + 600 + 50);
+        assert_eq!(body.spawn_time(), 15);
+        assert_eq!(body.len(), 5);
+        assert_eq!(body.count(Move), 1);
+    }
+
+    #[test]
+    fn repeats_within_limits() {
+        // limited by energy
+        let body = BodyBuilder::new()
+            .prefix(&[Carry])
+            .pattern(&[Work, Work, Move])
+            .max_energy(1050)
+            .build()
+            .unwrap();
+        assert_eq!(body.count(Work), 8);
+        assert_eq!(body.count(Carry), 1);
+        assert_eq!(body.cost(), 50 + 4 * 250);
+
+        // limited by size, with the suffix still fitting
+        let body = BodyBuilder::new()
+            .pattern(&[Carry, Carry, Move])
+            .suffix(&[Work, Move])
+            .build()
+            .unwrap();
+        assert_eq!(body.len(), 50);
+        assert_eq!(&body.parts()[48..], [Work, Move]);
+
+        // limited by repeats
+        let body = BodyBuilder::new()
+            .pattern(&[Attack, Move])
+            .max_repeats(3)
+            .max_energy(10_000)
+            .build()
+            .unwrap();
+        assert_eq!(body.parts(), [Attack, Move, Attack, Move, Attack, Move]);
+    }
+
+    #[test]
+    fn build_errors() {
+        assert_eq!(BodyBuilder::new().build(), Err(BodyError::Empty));
+        assert_eq!(
+            BodyBuilder::new()
+                .prefix(&[Claim, Move])
+                .pattern(&[Move])
+                .max_energy(600)
+                .build(),
+            Err(BodyError::TooExpensive {
+                cost: 700,
+                max_energy: 600
+            })
+        );
+        assert_eq!(
+            BodyBuilder::new()
+                .prefix(&[Claim, Move])
+                .max_energy(600)
+                .build(),
+            Err(BodyError::TooExpensive {
+                cost: 650,
+                max_energy: 600
+            })
+        );
+        assert_eq!(
+            BodyBuilder::new()
+                .prefix(&[Tough; 30])
+                .pattern(&[Move; 25])
+                .build(),
+            Err(BodyError::TooManyParts { len: 55 })
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        let builder = BodyBuilder::new()
+            .prefix(&[Move, Heal])
+            .pattern(&[RangedAttack, Move, Tough])
+            .max_repeats(2);
+
+        let body = builder
+            .clone()
+            .order(BodyOrder::ToughFirstMoveLast)
+            .build()
+            .unwrap();
+        assert_eq!(
+            body.parts(),
+            [
+                Tough,
+                Tough,
+                Heal,
+                RangedAttack,
+                RangedAttack,
+                Move,
+                Move,
+                Move
+            ]
+        );
+
+        let body = builder.order(BodyOrder::Grouped).build().unwrap();
+        assert_eq!(
+            body.parts(),
+            [
+                Tough,
+                Tough,
+                RangedAttack,
+                RangedAttack,
+                Heal,
+                Move,
+                Move,
+                Move
+            ]
+        );
+    }
+}
@@ -0,0 +1,328 @@
+//! Damage and healing of a creep's body, part by part.
+use crate::constants::{
+    Boost, Part, CREEP_HITS_PER_PART, RANGED_ATTACK_POWER, RANGED_MASS_ATTACK_POWER_RANGE_1,
+    RANGED_MASS_ATTACK_POWER_RANGE_2, RANGED_MASS_ATTACK_POWER_RANGE_3,
+};
+
+use super::{stats::boost_factor, BodyStats, LocalBodyPart};
+
+impl BodyStats {
+    /// Gets the damage dealt by a ranged mass attack to a target at the given
+    /// range, falling off with [`RANGED_MASS_ATTACK_POWER_RANGE_1`] and the
+    /// similar constants for ranges 2 and 3.
+    ///
+    /// Targets further than range 3 take no damage.
+    pub fn ranged_mass_attack(&self, range: u32) -> u32 {
+        let power_per_part = match range {
+            0 | 1 => RANGED_MASS_ATTACK_POWER_RANGE_1,
+            2 => RANGED_MASS_ATTACK_POWER_RANGE_2,
+            3 => RANGED_MASS_ATTACK_POWER_RANGE_3,
+            _ => return 0,
+        };
+        // each part's boost multiplies its mass attack the same way as its
+        // ranged attack
+        self.ranged_attack / RANGED_ATTACK_POWER * power_per_part
+    }
+}
+
+/// The hits of each part of a creep's body, for simulating the damage and
+/// healing it takes.
+///
+/// Like the game, damage is taken by the parts at the start of the body
+/// first, and healing restores the parts at the end of the body first. Parts
+/// with a [`Boost::Tough`] boost reduce the damage taken while they have hits
+/// left.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Part, ResourceType},
+///     local::{CreepHealth, LocalBodyPart},
+/// };
+///
+/// let mut parts = vec![LocalBodyPart::new(
+///     Part::Tough,
+///     Some(ResourceType::CatalyzedGhodiumAlkalide),
+/// )];
+/// parts.extend([Part::Attack, Part::Attack, Part::Move].map(LocalBodyPart::from));
+/// let mut creep = CreepHealth::new(parts);
+///
+/// // the tough part takes 30% of the damage it absorbs
+/// assert_eq!(creep.effective_damage(600), 367);
+/// assert_eq!(creep.heal_to_survive(600), 0);
+///
+/// creep.apply_damage(600);
+/// assert_eq!(creep.hits(), 33);
+/// assert_eq!(creep.active_parts(Part::Attack), 0);
+/// assert_eq!(creep.active_parts(Part::Move), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CreepHealth {
+    parts: Vec<LocalBodyPart>,
+}
+
+impl CreepHealth {
+    /// Creates a creep with the given parts, keeping their hits.
+    ///
+    /// The hits of the parts are redistributed the way the game does, so the
+    /// parts at the end of the body are undamaged.
+    pub fn new(parts: Vec<LocalBodyPart>) -> Self {
+        let mut creep = CreepHealth { parts };
+        creep.set_hits(creep.hits());
+        creep
+    }
+
+    /// Gets the parts of the creep's body, with their current hits.
+    #[inline]
+    pub fn parts(&self) -> &[LocalBodyPart] {
+        &self.parts
+    }
+
+    /// Gets the current hits of the creep.
+    pub fn hits(&self) -> u32 {
+        self.parts.iter().map(|part| part.hits).sum()
+    }
+
+    /// Gets the maximum hits of the creep.
+    pub fn hits_max(&self) -> u32 {
+        self.parts.len() as u32 * CREEP_HITS_PER_PART
+    }
+
+    /// Whether the creep has no hits left.
+    pub fn is_dead(&self) -> bool {
+        self.hits() == 0
+    }
+
+    /// Gets the number of parts of the given type which still have hits, and
+    /// so still have an effect.
+    pub fn active_parts(&self, part: Part) -> usize {
+        self.parts
+            .iter()
+            .filter(|body_part| body_part.part == part && body_part.is_active())
+            .count()
+    }
+
+    /// Gets the stats of the creep's active parts.
+    pub fn stats(&self) -> BodyStats {
+        BodyStats::new(&self.parts)
+    }
+
+    /// Gets the hits the creep would lose from the given total damage in one
+    /// tick, after reduction by boosted tough parts, without limiting it to
+    /// the creep's current hits.
+    pub fn effective_damage(&self, damage: u32) -> u32 {
+        damage - self.damage_reduction(damage)
+    }
+
+    /// Gets the least total damage in one tick which would kill the creep,
+    /// without any healing.
+    pub fn effective_hits(&self) -> u32 {
+        let hits = self.hits();
+        // the best tough boost takes 30% of damage, so more than 4 times the
+        // hits is always enough
+        let (mut low, mut high) = (hits, hits.saturating_mul(4));
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.effective_damage(mid) >= hits {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+        low
+    }
+
+    /// Gets the healing the creep needs in the same tick as taking the given
+    /// total damage to survive it.
+    pub fn heal_to_survive(&self, damage: u32) -> u32 {
+        (self.effective_damage(damage) + 1).saturating_sub(self.hits())
+    }
+
+    /// Applies the given total damage for a tick, returning the hits lost.
+    pub fn apply_damage(&mut self, damage: u32) -> u32 {
+        self.apply_tick(damage, 0)
+    }
+
+    /// Applies the given total healing for a tick, returning the hits
+    /// restored.
+    pub fn apply_heal(&mut self, heal: u32) -> u32 {
+        let hits = self.hits();
+        let healed = heal.min(self.hits_max() - hits);
+        self.set_hits(hits + healed);
+        healed
+    }
+
+    /// Applies the given total damage and healing taken in the same tick,
+    /// returning the hits lost after healing.
+    ///
+    /// Damage is reduced by the tough parts the creep has before the tick,
+    /// and the creep only dies if the damage is more than its hits and the
+    /// healing together.
+    pub fn apply_tick(&mut self, damage: u32, heal: u32) -> u32 {
+        let hits = self.hits();
+        let remaining = (hits + heal)
+            .saturating_sub(self.effective_damage(damage))
+            .min(self.hits_max());
+        self.set_hits(remaining);
+        hits.saturating_sub(remaining)
+    }
+
+    /// Calculates the damage absorbed by boosted tough parts, in the order
+    /// they take damage.
+    fn damage_reduction(&self, damage: u32) -> u32 {
+        // the game skips the reduction entirely for creeps without boosts
+        if self.parts.iter().all(|part| part.boost.is_none()) {
+            return 0;
+        }
+
+        let mut remaining = damage as f64;
+        let mut reduction = 0.0;
+        for part in &self.parts {
+            if remaining <= 0.0 {
+                break;
+            }
+            let ratio = match part.boost_effect() {
+                Some(Boost::Tough(ratio)) => boost_factor(ratio),
+                _ => 1.0,
+            };
+            let absorbed = (part.hits as f64 / ratio).min(remaining);
+            reduction += absorbed * (1.0 - ratio);
+            remaining -= absorbed;
+        }
+        reduction.round() as u32
+    }
+
+    /// Sets the creep's total hits, filling the parts from the end of the
+    /// body.
+    fn set_hits(&mut self, hits: u32) {
+        let mut remaining = hits;
+        for part in self.parts.iter_mut().rev() {
+            part.hits = remaining.min(CREEP_HITS_PER_PART);
+            remaining -= part.hits;
+        }
+    }
+}
+
+impl From<Vec<LocalBodyPart>> for CreepHealth {
+    fn from(parts: Vec<LocalBodyPart>) -> Self {
+        CreepHealth::new(parts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::ResourceType::{self, *};
+
+    use Part::*;
+
+    fn creep(parts: &[(Part, Option<ResourceType>)]) -> CreepHealth {
+        CreepHealth::new(
+            parts
+                .iter()
+                .map(|&(part, boost)| LocalBodyPart::new(part, boost))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn unboosted_damage_and_heal() {
+        let mut creep = creep(&[(Tough, None), (Work, None), (Carry, None), (Move, None)]);
+        assert_eq!(creep.effective_damage(150), 150);
+        assert_eq!(creep.effective_hits(), 400);
+
+        assert_eq!(creep.apply_damage(150), 150);
+        assert_eq!(
+            creep
+                .parts()
+                .iter()
+                .map(|part| part.hits)
+                .collect::<Vec<_>>(),
+            [0, 50, 100, 100]
+        );
+        assert_eq!(creep.active_parts(Tough), 0);
+        assert_eq!(creep.active_parts(Work), 1);
+
+        assert_eq!(creep.apply_heal(120), 120);
+        assert_eq!(
+            creep
+                .parts()
+                .iter()
+                .map(|part| part.hits)
+                .collect::<Vec<_>>(),
+            [70, 100, 100, 100]
+        );
+        assert_eq!(creep.apply_heal(120), 30);
+
+        // damage and healing in the same tick
+        assert_eq!(creep.heal_to_survive(450), 51);
+        assert_eq!(creep.clone().apply_tick(450, 50), 400);
+        let mut dying = creep.clone();
+        dying.apply_tick(450, 50);
+        assert!(dying.is_dead());
+        let mut surviving = creep;
+        surviving.apply_tick(450, 51);
+        assert_eq!(surviving.hits(), 1);
+    }
+
+    #[test]
+    fn tough_boosts() {
+        let creep = creep(&[
+            (Tough, Some(GhodiumOxide)),
+            (Tough, Some(CatalyzedGhodiumAlkalide)),
+            (Move, None),
+        ]);
+        // the first part absorbs 142.86 damage, taking 70% of it, then the
+        // second absorbs 333.33, taking 30%
+        assert_eq!(creep.effective_damage(100), 70);
+        assert_eq!(creep.effective_damage(200), 117);
+        assert_eq!(creep.effective_damage(300), 147);
+
+        let effective_hits = creep.effective_hits();
+        assert!(creep.effective_damage(effective_hits) >= 300);
+        assert!(creep.effective_damage(effective_hits - 1) < 300);
+        assert_eq!(effective_hits, 576);
+
+        // boosts on other part types don't reduce damage, and damaged tough
+        // parts reduce less
+        let mut creep = self::creep(&[
+            (Attack, Some(CatalyzedUtriumAcid)),
+            (Tough, Some(CatalyzedGhodiumAlkalide)),
+            (Move, None),
+        ]);
+        assert_eq!(creep.effective_damage(100), 100);
+        assert_eq!(creep.apply_damage(150), 115);
+        assert_eq!(creep.parts()[1].hits, 85);
+        assert_eq!(creep.effective_damage(100), 30);
+    }
+
+    #[test]
+    fn creating_redistributes_hits() {
+        let mut parts = vec![LocalBodyPart::from(Move); 3];
+        parts[2].hits = 40;
+        let creep = CreepHealth::new(parts);
+        assert_eq!(
+            creep
+                .parts()
+                .iter()
+                .map(|part| part.hits)
+                .collect::<Vec<_>>(),
+            [40, 100, 100]
+        );
+        assert_eq!(creep.hits_max(), 300);
+    }
+
+    #[test]
+    fn ranged_mass_attack_falloff() {
+        let stats = BodyStats::new(&[
+            LocalBodyPart::new(RangedAttack, Some(CatalyzedKeaniumAlkalide)),
+            LocalBodyPart::new(RangedAttack, None),
+        ]);
+        assert_eq!(stats.ranged_mass_attack(0), 50);
+        assert_eq!(stats.ranged_mass_attack(1), 50);
+        assert_eq!(stats.ranged_mass_attack(2), 20);
+        assert_eq!(stats.ranged_mass_attack(3), 5);
+        assert_eq!(stats.ranged_mass_attack(4), 0);
+    }
+}
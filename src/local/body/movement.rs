@@ -0,0 +1,466 @@
+//! Fatigue and the time a creep takes to move along a path.
+use std::{error::Error, fmt};
+
+use crate::{
+    constants::{
+        Boost, Part, Terrain, CARRY_CAPACITY, MOVE_COST_PLAIN, MOVE_COST_ROAD, MOVE_COST_SWAMP,
+        MOVE_POWER,
+    },
+    local::{LocalRoomTerrain, Position, RoomName, RoomXY, RoomXYSet},
+};
+
+use super::LocalBodyPart;
+
+/// How much fatigue a creep generates when moving and how quickly it
+/// recovers, given its body and the amount of resources it's carrying.
+///
+/// Each part other than [`Part::Move`] generates fatigue for each step the
+/// creep takes, based on the move cost of the tile it steps onto. Carry parts
+/// only generate fatigue while holding resources; like the game, the
+/// resources fill the active carry parts from the end of the body. Each
+/// active move part removes [`MOVE_POWER`] fatigue per tick, multiplied by
+/// its boost, and the creep can only move once its fatigue is back to 0.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Part, MOVE_COST_PLAIN, MOVE_COST_ROAD},
+///     local::{LocalBodyPart, MovementStats},
+/// };
+///
+/// let body: Vec<LocalBodyPart> = [Part::Carry, Part::Carry, Part::Move]
+///     .into_iter()
+///     .map(Into::into)
+///     .collect();
+///
+/// // empty carry parts don't slow the creep down
+/// let empty = MovementStats::new(&body, 0);
+/// assert_eq!(empty.ticks_per_step(MOVE_COST_PLAIN), Some(1));
+///
+/// let full = MovementStats::new(&body, 100);
+/// assert_eq!(full.ticks_per_step(MOVE_COST_PLAIN), Some(2));
+/// assert_eq!(full.ticks_per_step(MOVE_COST_ROAD), Some(1));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MovementStats {
+    /// The number of parts generating fatigue when moving.
+    pub weight: u32,
+    /// The fatigue removed each tick by the creep's active move parts.
+    pub fatigue_reduction: u32,
+}
+
+impl MovementStats {
+    /// Calculates the movement stats of a body with the given parts, carrying
+    /// the given total amount of resources.
+    pub fn new(parts: &[LocalBodyPart], carried: u32) -> Self {
+        let mut weight = 0;
+        let mut fatigue_reduction = 0;
+        let mut remaining_load = carried;
+
+        for part in parts.iter().rev() {
+            match part.part {
+                Part::Move => {
+                    if part.is_active() {
+                        fatigue_reduction += MOVE_POWER
+                            * match part.boost_effect() {
+                                Some(Boost::Move(multiplier)) => multiplier,
+                                _ => 1,
+                            };
+                    }
+                }
+                Part::Carry => {
+                    if part.is_active() && remaining_load > 0 {
+                        let capacity = CARRY_CAPACITY
+                            * match part.boost_effect() {
+                                Some(Boost::Carry(multiplier)) => multiplier,
+                                _ => 1,
+                            };
+                        remaining_load = remaining_load.saturating_sub(capacity);
+                        weight += 1;
+                    }
+                }
+                // damaged parts still generate fatigue
+                _ => weight += 1,
+            }
+        }
+
+        MovementStats {
+            weight,
+            fatigue_reduction,
+        }
+    }
+
+    /// Whether the creep has any active move parts, which it needs to move at
+    /// all.
+    #[inline]
+    pub fn can_move(&self) -> bool {
+        self.fatigue_reduction > 0
+    }
+
+    /// Gets the fatigue generated by stepping onto a tile with the given move
+    /// cost, such as [`MOVE_COST_PLAIN`].
+    #[inline]
+    pub fn fatigue_per_step(&self, move_cost: u32) -> u32 {
+        self.weight * move_cost
+    }
+
+    /// Gets the number of ticks taken by stepping onto a tile with the given
+    /// move cost and waiting for the creep's fatigue to return to 0, or
+    /// `None` if the creep can't move.
+    pub fn ticks_per_step(&self, move_cost: u32) -> Option<u32> {
+        if !self.can_move() {
+            return None;
+        }
+        // the step's own tick also removes fatigue
+        let fatigue = self.fatigue_per_step(move_cost);
+        Some(fatigue.div_ceil(self.fatigue_reduction).max(1))
+    }
+
+    /// Gets the number of ticks taken to step onto tiles with each of the
+    /// given move costs in turn, starting without fatigue, or `None` if the
+    /// creep can't move.
+    ///
+    /// This counts up to the tick the creep arrives on the last tile, not
+    /// including any time it would then need to recover from fatigue.
+    pub fn travel_ticks(&self, move_costs: impl IntoIterator<Item = u32>) -> Option<u32> {
+        let mut ticks = 0;
+        let mut fatigue: u32 = 0;
+        for move_cost in move_costs {
+            if !self.can_move() {
+                return None;
+            }
+            // wait until the creep can move
+            ticks += fatigue.div_ceil(self.fatigue_reduction);
+            ticks += 1;
+            fatigue = self
+                .fatigue_per_step(move_cost)
+                .saturating_sub(self.fatigue_reduction);
+        }
+        Some(ticks)
+    }
+}
+
+/// Gets the move cost of stepping onto a tile, or `None` if it's a wall
+/// without a road.
+///
+/// Tiles in `roads` use [`MOVE_COST_ROAD`], including tunnels through walls;
+/// other tiles use the move cost of their terrain.
+pub fn tile_move_cost(
+    terrain: &LocalRoomTerrain,
+    roads: Option<&RoomXYSet>,
+    xy: RoomXY,
+) -> Option<u32> {
+    if roads.is_some_and(|roads| roads.contains(xy)) {
+        return Some(MOVE_COST_ROAD);
+    }
+    match terrain.get_xy(xy) {
+        Terrain::Plain => Some(MOVE_COST_PLAIN),
+        Terrain::Swamp => Some(MOVE_COST_SWAMP),
+        Terrain::Wall => None,
+    }
+}
+
+/// Gets the number of ticks a creep with the given body, carrying the given
+/// total amount of resources, takes to move along a path, until it arrives on
+/// the last position of the path.
+///
+/// The path is the positions the creep steps onto in order, not including
+/// its starting position, as returned by [`local_search`]. The creep starts
+/// without fatigue. Stepping onto an exit tile moves the creep into the next
+/// room on the same tick, so the position across the edge costs no time or
+/// fatigue.
+///
+/// `terrain` is called to get the terrain of each room in the path, and
+/// `roads` to get the tiles with roads in each room, which may be `None` for
+/// rooms without roads.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Part, ROOM_AREA},
+///     local::{
+///         path_travel_ticks, LocalBodyPart, LocalRoomTerrain, Position, RoomCoordinate, RoomName,
+///         RoomXY, RoomXYSet,
+///     },
+/// };
+///
+/// let room: RoomName = "W1N1".parse().unwrap();
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let mut roads = RoomXYSet::new();
+/// roads.insert(RoomXY::checked_new(11, 10).unwrap());
+/// roads.insert(RoomXY::checked_new(12, 10).unwrap());
+///
+/// let path: Vec<Position> = (11..=14)
+///     .map(|x| {
+///         Position::new(
+///             RoomCoordinate::new(x).unwrap(),
+///             RoomCoordinate::new(10).unwrap(),
+///             room,
+///         )
+///     })
+///     .collect();
+///
+/// let body: Vec<LocalBodyPart> = [Part::Work, Part::Work, Part::Move]
+///     .into_iter()
+///     .map(Into::into)
+///     .collect();
+///
+/// // 1 tick for each road and 2 for the first plain, arriving on the last
+/// // plain without waiting to recover from its fatigue
+/// let ticks = path_travel_ticks(&body, 0, &path, |_| Some(&terrain), |_| Some(&roads));
+/// assert_eq!(ticks, Ok(5));
+/// ```
+///
+/// [`local_search`]: crate::local::local_search
+pub fn path_travel_ticks<'t, 'r, T, R>(
+    parts: &[LocalBodyPart],
+    carried: u32,
+    path: &[Position],
+    mut terrain: T,
+    mut roads: R,
+) -> Result<u32, TravelError>
+where
+    T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+    R: FnMut(RoomName) -> Option<&'r RoomXYSet>,
+{
+    let stats = MovementStats::new(parts, carried);
+    if !stats.can_move() && !path.is_empty() {
+        return Err(TravelError::NoMoveParts);
+    }
+
+    let mut current_room = None;
+    let mut previous: Option<Position> = None;
+    let mut move_costs = Vec::with_capacity(path.len());
+    for &pos in path {
+        let room_name = pos.room_name();
+        // consecutive positions in different rooms are the automatic move
+        // from an exit tile to the next room, not a step of their own
+        if previous
+            .replace(pos)
+            .is_some_and(|previous| previous.room_name() != room_name)
+        {
+            continue;
+        }
+        let (room_terrain, room_roads) = match current_room {
+            Some((name, room_terrain, room_roads)) if name == room_name => {
+                (room_terrain, room_roads)
+            }
+            _ => {
+                let room_terrain =
+                    terrain(room_name).ok_or(TravelError::MissingTerrain(room_name))?;
+                let room_roads = roads(room_name);
+                current_room = Some((room_name, room_terrain, room_roads));
+                (room_terrain, room_roads)
+            }
+        };
+        let move_cost =
+            tile_move_cost(room_terrain, room_roads, pos.xy()).ok_or(TravelError::Blocked(pos))?;
+        move_costs.push(move_cost);
+    }
+
+    Ok(stats.travel_ticks(move_costs).unwrap_or_default())
+}
+
+/// An error representing when the time to travel along a path can't be
+/// calculated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TravelError {
+    /// The creep has no active move parts, so it can't move.
+    NoMoveParts,
+    /// The terrain of a room in the path wasn't available.
+    MissingTerrain(RoomName),
+    /// A position in the path is a wall without a road.
+    Blocked(Position),
+}
+
+impl Error for TravelError {}
+
+impl fmt::Display for TravelError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TravelError::NoMoveParts => write!(f, "creep has no active move parts"),
+            TravelError::MissingTerrain(room_name) => {
+                write!(f, "no terrain available for room {room_name}")
+            }
+            TravelError::Blocked(pos) => write!(f, "path is blocked by a wall at {pos}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::constants::{ResourceType, ROOM_AREA};
+
+    use Part::*;
+
+    fn body(parts: &[Part]) -> Vec<LocalBodyPart> {
+        parts.iter().map(|&part| part.into()).collect()
+    }
+
+    /// Steps through the game's fatigue rules one tick at a time.
+    fn simulate(stats: MovementStats, move_costs: &[u32]) -> u32 {
+        let mut ticks = 0;
+        let mut fatigue = 0;
+        for &move_cost in move_costs {
+            loop {
+                ticks += 1;
+                let moved = fatigue == 0;
+                if moved {
+                    fatigue += stats.fatigue_per_step(move_cost);
+                }
+                fatigue = fatigue.saturating_sub(stats.fatigue_reduction);
+                if moved {
+                    break;
+                }
+            }
+        }
+        ticks
+    }
+
+    #[test]
+    fn matches_tick_simulation() {
+        let costs = [
+            MOVE_COST_PLAIN,
+            MOVE_COST_SWAMP,
+            MOVE_COST_ROAD,
+            MOVE_COST_SWAMP,
+            MOVE_COST_PLAIN,
+            MOVE_COST_ROAD,
+        ];
+        let bodies = [
+            body(&[Move]),
+            body(&[Work, Move]),
+            body(&[Work, Work, Work, Move]),
+            body(&[Carry, Carry, Carry, Carry, Move, Move]),
+            body(&[Tough, Attack, Attack, Heal, Move, Move, Move]),
+        ];
+        for parts in &bodies {
+            for carried in [0, 30, 50, 120, 1000] {
+                let stats = MovementStats::new(parts, carried);
+                assert_eq!(stats.travel_ticks(costs), Some(simulate(stats, &costs)));
+                for cost in costs {
+                    // the time taken by each step after the first
+                    assert_eq!(
+                        stats.ticks_per_step(cost),
+                        Some(simulate(stats, &[cost; 2]) - simulate(stats, &[cost]))
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn carry_weight() {
+        let parts = body(&[Carry, Carry, Carry, Move]);
+        assert_eq!(MovementStats::new(&parts, 0).weight, 0);
+        assert_eq!(MovementStats::new(&parts, 1).weight, 1);
+        assert_eq!(MovementStats::new(&parts, 50).weight, 1);
+        assert_eq!(MovementStats::new(&parts, 51).weight, 2);
+        assert_eq!(MovementStats::new(&parts, 1000).weight, 3);
+
+        // boosted parts hold more, and are filled from the end of the body
+        let parts = [
+            LocalBodyPart::new(Carry, None),
+            LocalBodyPart::new(Carry, Some(ResourceType::CatalyzedKeaniumAcid)),
+            LocalBodyPart::new(Move, None),
+        ];
+        assert_eq!(MovementStats::new(&parts, 200).weight, 1);
+        assert_eq!(MovementStats::new(&parts, 201).weight, 2);
+
+        // destroyed carry parts hold nothing, but other destroyed parts
+        // still weigh the creep down
+        let mut parts = body(&[Work, Carry, Carry, Move]);
+        parts[0].hits = 0;
+        parts[2].hits = 0;
+        assert_eq!(MovementStats::new(&parts, 10).weight, 2);
+    }
+
+    #[test]
+    fn move_parts() {
+        let mut parts = [
+            LocalBodyPart::new(Work, None),
+            LocalBodyPart::new(Work, None),
+            LocalBodyPart::new(Work, None),
+            LocalBodyPart::new(Work, None),
+            LocalBodyPart::new(Move, Some(ResourceType::CatalyzedZynthiumAlkalide)),
+        ];
+        let stats = MovementStats::new(&parts, 0);
+        assert_eq!(stats.fatigue_reduction, 8);
+        assert_eq!(stats.ticks_per_step(MOVE_COST_PLAIN), Some(1));
+        assert_eq!(stats.ticks_per_step(MOVE_COST_SWAMP), Some(5));
+
+        parts[4].hits = 0;
+        let stats = MovementStats::new(&parts, 0);
+        assert!(!stats.can_move());
+        assert_eq!(stats.travel_ticks([MOVE_COST_ROAD]), None);
+        assert_eq!(stats.travel_ticks([]), Some(0));
+    }
+
+    #[test]
+    fn path_across_rooms() {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        bits[crate::local::xy_to_terrain_index(RoomXY::checked_new(0, 10).unwrap())] = 2;
+        let east = LocalRoomTerrain::new_from_bits(bits);
+        let west = LocalRoomTerrain::new_from_bits(Box::new([1; ROOM_AREA]));
+        let mut tunnel = RoomXYSet::new();
+        tunnel.insert(RoomXY::checked_new(49, 10).unwrap());
+
+        let west_name: RoomName = "W0N0".parse().unwrap();
+        let east_name: RoomName = "E0N0".parse().unwrap();
+        let terrain = |room_name| {
+            if room_name == west_name {
+                Some(&west)
+            } else if room_name == east_name {
+                Some(&east)
+            } else {
+                None
+            }
+        };
+        let roads = |room_name| (room_name == west_name).then_some(&tunnel);
+
+        let pos = |x, room_name| {
+            Position::new(
+                crate::local::RoomCoordinate::new(x).unwrap(),
+                crate::local::RoomCoordinate::new(10).unwrap(),
+                room_name,
+            )
+        };
+        let parts = body(&[Work, Work, Move]);
+
+        // a road tunnel, then a free move across the edge onto the swamp, then
+        // arriving on a plain
+        let path = [pos(49, west_name), pos(0, east_name), pos(1, east_name)];
+        assert_eq!(
+            path_travel_ticks(&parts, 0, &path, terrain, roads),
+            Ok(1 + 1)
+        );
+        assert_eq!(
+            path_travel_ticks(&parts, 0, &path[..2], terrain, roads),
+            Ok(1)
+        );
+
+        let path = [pos(48, west_name)];
+        assert_eq!(
+            path_travel_ticks(&parts, 0, &path, terrain, roads),
+            Err(TravelError::Blocked(path[0]))
+        );
+
+        let path = [pos(1, "E1N0".parse().unwrap())];
+        assert_eq!(
+            path_travel_ticks(&parts, 0, &path, terrain, roads),
+            Err(TravelError::MissingTerrain(path[0].room_name()))
+        );
+
+        assert_eq!(
+            path_travel_ticks(&body(&[Work]), 0, &path, terrain, roads),
+            Err(TravelError::NoMoveParts)
+        );
+        assert_eq!(
+            path_travel_ticks(&body(&[Work]), 0, &[], terrain, roads),
+            Ok(0)
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! The per-tick capabilities of a creep's body, including boosts.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::{
+        Boost, Part, ResourceType, ATTACK_POWER, BUILD_POWER, CARRY_CAPACITY, CREEP_HITS_PER_PART,
+        DISMANTLE_POWER, HARVEST_DEPOSIT_POWER, HARVEST_MINERAL_POWER, HARVEST_POWER, HEAL_POWER,
+        RANGED_ATTACK_POWER, RANGED_HEAL_POWER, REPAIR_POWER, UPGRADE_CONTROLLER_POWER,
+    },
+    objects::BodyPart,
+};
+
+/// A body part of a creep, with its boost and remaining hits, stored in Rust
+/// memory.
+///
+/// This holds the same data as [`BodyPart`], which can be converted into it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct LocalBodyPart {
+    /// The type of the part.
+    pub part: Part,
+    /// The resource the part is boosted with, if any.
+    pub boost: Option<ResourceType>,
+    /// The remaining hits of the part, from 0 to [`CREEP_HITS_PER_PART`].
+    pub hits: u32,
+}
+
+impl LocalBodyPart {
+    /// Creates an undamaged part, boosted with the given resource.
+    #[inline]
+    pub const fn new(part: Part, boost: Option<ResourceType>) -> Self {
+        LocalBodyPart {
+            part,
+            boost,
+            hits: CREEP_HITS_PER_PART,
+        }
+    }
+
+    /// Whether the part has any hits left, and so still has an effect.
+    #[inline]
+    pub const fn is_active(&self) -> bool {
+        self.hits > 0
+    }
+
+    /// Gets the effect of the part's boost, if it's boosted with a resource
+    /// which boosts this type of part.
+    pub fn boost_effect(&self) -> Option<Boost> {
+        self.boost
+            .and_then(ResourceType::boost)
+            .filter(|boost| boost.part() == self.part)
+    }
+}
+
+impl From<Part> for LocalBodyPart {
+    fn from(part: Part) -> Self {
+        LocalBodyPart::new(part, None)
+    }
+}
+
+impl From<&BodyPart> for LocalBodyPart {
+    fn from(body_part: &BodyPart) -> Self {
+        LocalBodyPart {
+            part: body_part.part(),
+            boost: body_part.boost(),
+            hits: body_part.hits(),
+        }
+    }
+}
+
+impl From<BodyPart> for LocalBodyPart {
+    fn from(body_part: BodyPart) -> Self {
+        LocalBodyPart::from(&body_part)
+    }
+}
+
+/// The amount a creep's body can do each tick, counting only its active parts
+/// and applying their boosts.
+///
+/// Build, repair and upgrade amounts are rounded down after adding up every
+/// part, like the game does.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Part, ResourceType},
+///     local::{BodyStats, LocalBodyPart},
+/// };
+///
+/// let body = [
+///     LocalBodyPart::new(Part::Work, Some(ResourceType::CatalyzedUtriumAlkalide)),
+///     LocalBodyPart::new(Part::Work, None),
+///     LocalBodyPart::new(Part::Carry, Some(ResourceType::KeaniumHydride)),
+///     LocalBodyPart::new(Part::Move, None),
+/// ];
+/// let stats = BodyStats::new(&body);
+///
+/// // HARVEST_POWER * 7 + HARVEST_POWER
+/// assert_eq!(stats.harvest_energy, 16);
+/// assert_eq!(stats.build, 10);
+/// assert_eq!(stats.carry_capacity, 100);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct BodyStats {
+    /// Energy harvested from a source each tick.
+    pub harvest_energy: u32,
+    /// Minerals harvested from a mineral each tick.
+    pub harvest_mineral: u32,
+    /// Resources harvested from a deposit each tick.
+    pub harvest_deposit: u32,
+    /// Progress added to a construction site each tick, which uses the same
+    /// amount of energy.
+    pub build: u32,
+    /// Hits repaired each tick, using one energy per [`REPAIR_POWER`] hits
+    /// before boosts.
+    pub repair: u32,
+    /// Hits of damage dealt to a structure by dismantling each tick.
+    pub dismantle: u32,
+    /// Progress added to a controller each tick.
+    pub upgrade_controller: u32,
+    /// Damage dealt by a melee attack.
+    pub attack: u32,
+    /// Damage dealt by a ranged attack.
+    pub ranged_attack: u32,
+    /// Hits healed on an adjacent creep.
+    pub heal: u32,
+    /// Hits healed on a creep at range.
+    pub ranged_heal: u32,
+    /// The amount of resources the creep can carry.
+    pub carry_capacity: u32,
+}
+
+impl BodyStats {
+    /// Calculates the stats of a body with the given parts.
+    pub fn new(parts: &[LocalBodyPart]) -> Self {
+        let mut stats = BodyStats::default();
+        let mut build = 0.0;
+        let mut repair = 0.0;
+        let mut upgrade_controller = 0.0;
+
+        for part in parts.iter().filter(|part| part.is_active()) {
+            let boost = part.boost_effect();
+            match part.part {
+                Part::Work => {
+                    let harvest = match boost {
+                        Some(Boost::Harvest(multiplier)) => multiplier,
+                        _ => 1,
+                    };
+                    stats.harvest_energy += HARVEST_POWER * harvest;
+                    stats.harvest_mineral += HARVEST_MINERAL_POWER * harvest;
+                    stats.harvest_deposit += HARVEST_DEPOSIT_POWER * harvest;
+
+                    let build_and_repair = match boost {
+                        Some(Boost::BuildAndRepair(multiplier)) => boost_factor(multiplier),
+                        _ => 1.0,
+                    };
+                    build += BUILD_POWER as f64 * build_and_repair;
+                    repair += REPAIR_POWER as f64 * build_and_repair;
+
+                    stats.dismantle += DISMANTLE_POWER
+                        * match boost {
+                            Some(Boost::Dismantle(multiplier)) => multiplier,
+                            _ => 1,
+                        };
+
+                    upgrade_controller += UPGRADE_CONTROLLER_POWER as f64
+                        * match boost {
+                            Some(Boost::UpgradeController(multiplier)) => boost_factor(multiplier),
+                            _ => 1.0,
+                        };
+                }
+                Part::Attack => {
+                    stats.attack += ATTACK_POWER
+                        * match boost {
+                            Some(Boost::Attack(multiplier)) => multiplier,
+                            _ => 1,
+                        };
+                }
+                Part::RangedAttack => {
+                    stats.ranged_attack += RANGED_ATTACK_POWER
+                        * match boost {
+                            Some(Boost::RangedAttack(multiplier)) => multiplier,
+                            _ => 1,
+                        };
+                }
+                Part::Heal => {
+                    let heal = match boost {
+                        Some(Boost::Heal(multiplier)) => multiplier,
+                        _ => 1,
+                    };
+                    stats.heal += HEAL_POWER * heal;
+                    stats.ranged_heal += RANGED_HEAL_POWER * heal;
+                }
+                Part::Carry => {
+                    stats.carry_capacity += CARRY_CAPACITY
+                        * match boost {
+                            Some(Boost::Carry(multiplier)) => multiplier,
+                            _ => 1,
+                        };
+                }
+                _ => {}
+            }
+        }
+
+        stats.build = build as u32;
+        stats.repair = repair as u32;
+        stats.upgrade_controller = upgrade_controller as u32;
+        stats
+    }
+
+    /// Calculates the stats of an undamaged, unboosted body with the given
+    /// parts, such as a [`BodySpec`] before spawning.
+    ///
+    /// [`BodySpec`]: super::BodySpec
+    pub fn from_parts(parts: &[Part]) -> Self {
+        let parts: Vec<LocalBodyPart> = parts.iter().map(|&part| part.into()).collect();
+        BodyStats::new(&parts)
+    }
+}
+
+/// Converts a fractional boost multiplier to the decimal value the game uses.
+///
+/// The multipliers are all in tenths, which aren't exact in an `f32`; without
+/// rounding, values like `1.8` would come out slightly low and round down a
+/// whole point when multiplied.
+pub(super) fn boost_factor(multiplier: f32) -> f64 {
+    (multiplier as f64 * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    use Part::*;
+    use ResourceType::*;
+
+    #[test]
+    fn unboosted_stats() {
+        let stats = BodyStats::from_parts(&[Work, Work, Carry, Attack, RangedAttack, Heal, Move]);
+        assert_eq!(
+            stats,
+            BodyStats {
+                harvest_energy: 4,
+                harvest_mineral: 2,
+                harvest_deposit: 2,
+                build: 10,
+                repair: 200,
+                dismantle: 100,
+                upgrade_controller: 2,
+                attack: 30,
+                ranged_attack: 10,
+                heal: 12,
+                ranged_heal: 4,
+                carry_capacity: 50,
+            }
+        );
+    }
+
+    #[test]
+    fn boosted_stats() {
+        let work = |boost| LocalBodyPart::new(Work, Some(boost));
+        let stats = BodyStats::new(&[work(LemergiumAcid); 5]);
+        // 1.8 is inexact as an f32, but shouldn't round 45 down to 44
+        assert_eq!(stats.build, 45);
+        assert_eq!(stats.repair, 900);
+        assert_eq!(stats.harvest_energy, 10);
+
+        let stats = BodyStats::new(&[
+            work(GhodiumHydride),
+            work(GhodiumHydride),
+            work(GhodiumHydride),
+        ]);
+        assert_eq!(stats.upgrade_controller, 4);
+
+        let stats = BodyStats::new(&[
+            LocalBodyPart::new(Attack, Some(CatalyzedUtriumAcid)),
+            LocalBodyPart::new(RangedAttack, Some(KeaniumOxide)),
+            LocalBodyPart::new(Heal, Some(LemergiumAlkalide)),
+            LocalBodyPart::new(Carry, Some(CatalyzedKeaniumAcid)),
+        ]);
+        assert_eq!(stats.attack, 120);
+        assert_eq!(stats.ranged_attack, 20);
+        assert_eq!(stats.heal, 36);
+        assert_eq!(stats.ranged_heal, 12);
+        assert_eq!(stats.carry_capacity, 200);
+    }
+
+    #[test]
+    fn ignores_inactive_parts_and_mismatched_boosts() {
+        let stats = BodyStats::new(&[
+            LocalBodyPart {
+                part: Attack,
+                boost: None,
+                hits: 0,
+            },
+            LocalBodyPart {
+                part: Attack,
+                boost: None,
+                hits: 1,
+            },
+            // an attack boost doesn't apply to a work part
+            LocalBodyPart::new(Work, Some(CatalyzedUtriumAcid)),
+        ]);
+        assert_eq!(stats.attack, 30);
+        assert_eq!(stats.dismantle, 50);
+        assert_eq!(stats.harvest_energy, 2);
+    }
+}
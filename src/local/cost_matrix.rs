@@ -3,12 +3,12 @@ use std::ops::{Index, IndexMut};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    constants::ROOM_AREA,
+    constants::{Terrain, ROOM_AREA, ROOM_SIZE},
     objects::CostMatrix,
     traits::{CostMatrixGet, CostMatrixSet},
 };
 
-use super::{linear_index_to_xy, xy_to_linear_index, Position, RoomXY};
+use super::{linear_index_to_xy, xy_to_linear_index, LocalRoomTerrain, Position, RoomXY};
 
 /// A matrix of pathing costs for a room, stored in Rust memory.
 ///
@@ -95,6 +95,187 @@ impl LocalCostMatrix {
             .enumerate()
             .map(|(idx, val)| (linear_index_to_xy(idx), val))
     }
+
+    /// Create a `LocalCostMatrix` with the cost of each position set by the
+    /// terrain at that position.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use screeps::{
+    ///     constants::ROOM_AREA,
+    ///     local::{LocalCostMatrix, LocalRoomTerrain, RoomXY},
+    /// };
+    ///
+    /// let mut bits = Box::new([0; ROOM_AREA]);
+    /// bits[0] = 1;
+    /// let terrain = LocalRoomTerrain::new_from_bits(bits);
+    ///
+    /// let lcm = LocalCostMatrix::from_terrain(&terrain, 2, 10, u8::MAX);
+    /// assert_eq!(lcm.get(RoomXY::checked_new(0, 0).unwrap()), u8::MAX);
+    /// assert_eq!(lcm.get(RoomXY::checked_new(1, 0).unwrap()), 2);
+    /// ```
+    pub fn from_terrain(terrain: &LocalRoomTerrain, plain: u8, swamp: u8, wall: u8) -> Self {
+        let mut lcm = LocalCostMatrix::new();
+        for (xy, cost) in lcm.iter_mut() {
+            *cost = match terrain.get_xy(xy) {
+                Terrain::Plain => plain,
+                Terrain::Swamp => swamp,
+                Terrain::Wall => wall,
+            };
+        }
+        lcm
+    }
+
+    /// Adds the costs of another matrix to the costs of this one, saturating
+    /// at 255.
+    pub fn add_saturating(&mut self, other: &LocalCostMatrix) {
+        self.merge_with(other, u8::saturating_add);
+    }
+
+    /// Sets each cost of this matrix to the higher of it and the cost of
+    /// another matrix.
+    pub fn merge_max(&mut self, other: &LocalCostMatrix) {
+        self.merge_with(other, u8::max);
+    }
+
+    /// Sets each cost of this matrix to the lower of it and the cost of
+    /// another matrix.
+    pub fn merge_min(&mut self, other: &LocalCostMatrix) {
+        self.merge_with(other, u8::min);
+    }
+
+    #[inline]
+    fn merge_with(&mut self, other: &LocalCostMatrix, f: impl Fn(u8, u8) -> u8) {
+        for (cost, &other_cost) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *cost = f(*cost, other_cost);
+        }
+    }
+
+    /// Sets the cost of every position for which the predicate returns
+    /// `true`.
+    pub fn fill_where(&mut self, value: u8, mut predicate: impl FnMut(RoomXY) -> bool) {
+        for (xy, cost) in self.iter_mut() {
+            if predicate(xy) {
+                *cost = value;
+            }
+        }
+    }
+
+    /// Sets the cost of every position within the given range of a position,
+    /// measured the same way as [`RoomXY::get_range_to`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use screeps::local::{LocalCostMatrix, RoomXY};
+    ///
+    /// let mut lcm = LocalCostMatrix::new();
+    /// lcm.set_in_range(RoomXY::checked_new(1, 1).unwrap(), 3, 50);
+    ///
+    /// // clipped to the edge of the room
+    /// assert_eq!(lcm.iter().filter(|&(_, cost)| cost == 50).count(), 25);
+    /// ```
+    pub fn set_in_range(&mut self, xy: RoomXY, range: u8, value: u8) {
+        let clamp = |coord: u8| {
+            (
+                coord.saturating_sub(range) as usize,
+                coord.saturating_add(range).min(ROOM_SIZE - 1) as usize,
+            )
+        };
+        let (min_x, max_x) = clamp(xy.x.u8());
+        let (min_y, max_y) = clamp(xy.y.u8());
+
+        // each column is contiguous
+        for x in min_x..=max_x {
+            let column = x * ROOM_SIZE as usize;
+            self.bits[column + min_y..=column + max_y].fill(value);
+        }
+    }
+
+    /// Iterates over the positions where the cost of this matrix differs from
+    /// another, along with the cost in this matrix and the other.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use screeps::local::{LocalCostMatrix, RoomXY};
+    ///
+    /// let before = LocalCostMatrix::new();
+    /// let mut after = before.clone();
+    /// let xy = RoomXY::checked_new(20, 30).unwrap();
+    /// after.set(xy, 5);
+    ///
+    /// assert_eq!(before.diff(&after).collect::<Vec<_>>(), [(xy, 0, 5)]);
+    /// ```
+    pub fn diff<'a>(
+        &'a self,
+        other: &'a LocalCostMatrix,
+    ) -> impl Iterator<Item = (RoomXY, u8, u8)> + 'a {
+        // compare 8 positions at a time to quickly skip over unchanged areas
+        self.bits
+            .chunks(8)
+            .zip(other.bits.chunks(8))
+            .enumerate()
+            .filter(|(_, (chunk, other_chunk))| chunk != other_chunk)
+            .flat_map(|(chunk_idx, (chunk, other_chunk))| {
+                chunk
+                    .iter()
+                    .zip(other_chunk)
+                    .enumerate()
+                    .filter(|(_, (cost, other_cost))| cost != other_cost)
+                    .map(move |(idx, (&cost, &other_cost))| {
+                        (linear_index_to_xy(chunk_idx * 8 + idx), cost, other_cost)
+                    })
+            })
+    }
+
+    /// Converts this matrix into the array of numbers produced by the
+    /// JavaScript [`CostMatrix.serialize`], for storage in memory alongside
+    /// matrices serialized by JS code.
+    ///
+    /// The array has 625 values, each packing 4 consecutive costs into a
+    /// little-endian `u32`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use screeps::local::{LocalCostMatrix, RoomXY};
+    ///
+    /// let mut lcm = LocalCostMatrix::new();
+    /// lcm.set(RoomXY::checked_new(0, 1).unwrap(), 255);
+    ///
+    /// let serialized = lcm.to_js_serialized();
+    /// assert_eq!(serialized.len(), 625);
+    /// assert_eq!(serialized[0], 0xFF00);
+    /// assert_eq!(LocalCostMatrix::from_js_serialized(&serialized), Some(lcm));
+    /// ```
+    ///
+    /// [`CostMatrix.serialize`]: https://docs.screeps.com/api/#PathFinder.CostMatrix.serialize
+    pub fn to_js_serialized(&self) -> Vec<u32> {
+        self.bits
+            .chunks_exact(4)
+            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+            .collect()
+    }
+
+    /// Creates a matrix from the array of numbers produced by the JavaScript
+    /// [`CostMatrix.serialize`] or [`LocalCostMatrix::to_js_serialized`].
+    ///
+    /// Returns `None` if the array doesn't have exactly 625 values.
+    ///
+    /// [`CostMatrix.serialize`]: https://docs.screeps.com/api/#PathFinder.CostMatrix.serialize
+    pub fn from_js_serialized(serialized: &[u32]) -> Option<Self> {
+        if serialized.len() * 4 != ROOM_AREA {
+            return None;
+        }
+
+        let mut bits = [0; ROOM_AREA];
+        for (chunk, value) in bits.chunks_exact_mut(4).zip(serialized) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        Some(LocalCostMatrix { bits })
+    }
 }
 
 impl From<LocalCostMatrix> for Vec<u8> {
@@ -194,3 +375,101 @@ mod serde_impls {
         Ok(bits_slice.try_into().unwrap())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn js_serialized_round_trip() {
+        let mut lcm = LocalCostMatrix::new();
+        for (idx, (_, cost)) in lcm.iter_mut().enumerate() {
+            *cost = (idx % 251) as u8;
+        }
+
+        let serialized = lcm.to_js_serialized();
+        assert_eq!(serialized.len(), ROOM_AREA / 4);
+        // costs 0, 1, 2 and 3 of the first column, lowest byte first
+        assert_eq!(serialized[0], 0x03020100);
+        assert_eq!(LocalCostMatrix::from_js_serialized(&serialized), Some(lcm));
+
+        assert_eq!(LocalCostMatrix::from_js_serialized(&serialized[1..]), None);
+        assert_eq!(LocalCostMatrix::from_js_serialized(&[]), None);
+    }
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn merge() {
+        let mut a = LocalCostMatrix::new_with_value(200);
+        a.set(xy(1, 1), 10);
+        let mut b = LocalCostMatrix::new_with_value(100);
+        b.set(xy(2, 2), 0);
+
+        let mut sum = a.clone();
+        sum.add_saturating(&b);
+        assert_eq!(sum.get(xy(0, 0)), 255);
+        assert_eq!(sum.get(xy(1, 1)), 110);
+        assert_eq!(sum.get(xy(2, 2)), 200);
+
+        let mut max = a.clone();
+        max.merge_max(&b);
+        assert_eq!(max.get(xy(0, 0)), 200);
+        assert_eq!(max.get(xy(1, 1)), 100);
+
+        let mut min = a.clone();
+        min.merge_min(&b);
+        assert_eq!(min.get(xy(0, 0)), 100);
+        assert_eq!(min.get(xy(1, 1)), 10);
+        assert_eq!(min.get(xy(2, 2)), 0);
+    }
+
+    #[test]
+    fn fill_and_set_in_range() {
+        let mut lcm = LocalCostMatrix::new();
+        lcm.fill_where(1, |xy| xy.is_room_edge());
+        assert_eq!(lcm.iter().filter(|&(_, cost)| cost == 1).count(), 196);
+
+        let center = xy(25, 25);
+        lcm.set_in_range(center, 2, 9);
+        for (xy, cost) in lcm.iter() {
+            let expected = if xy.get_range_to(center) <= 2 {
+                9
+            } else if xy.is_room_edge() {
+                1
+            } else {
+                0
+            };
+            assert_eq!(cost, expected, "{xy}");
+        }
+
+        let mut lcm = LocalCostMatrix::new();
+        lcm.set_in_range(xy(49, 0), u8::MAX, 3);
+        assert_eq!(lcm, LocalCostMatrix::new_with_value(3));
+    }
+
+    #[test]
+    fn diff() {
+        let a = LocalCostMatrix::new();
+        assert_eq!(a.diff(&a).count(), 0);
+
+        let mut b = a.clone();
+        let changed = [xy(0, 0), xy(0, 7), xy(0, 8), xy(49, 49)];
+        for (value, &xy) in changed.iter().enumerate() {
+            b.set(xy, value as u8 + 1);
+        }
+
+        let diff: Vec<_> = a.diff(&b).collect();
+        assert_eq!(
+            diff,
+            [
+                (xy(0, 0), 0, 1),
+                (xy(0, 7), 0, 2),
+                (xy(0, 8), 0, 3),
+                (xy(49, 49), 0, 4)
+            ]
+        );
+    }
+}
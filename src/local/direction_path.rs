@@ -0,0 +1,502 @@
+//! Compact paths stored as a starting position and a chain of directions.
+use std::fmt;
+
+use num_traits::FromPrimitive;
+
+use crate::constants::Direction;
+
+use super::Position;
+
+/// A path stored as a starting position and the direction of each step,
+/// packed into 4 bits each.
+///
+/// This takes far less memory than a `Vec<Position>`, and serializes to a
+/// compact form: in human-readable formats the directions are a string with
+/// one digit per step, as used by [`Room::serialize_path`], and in binary
+/// formats they stay packed.
+///
+/// Positions along the path use world coordinates, so a path can cross room
+/// edges; stepping off the edge tile of one room lands on the edge tile of
+/// the next, as creeps are moved in game. A diagonal step off an edge tile
+/// only moves the creep across the edge, so it lands directly opposite.
+///
+/// Creeps can follow a `DirectionPath` with
+/// [`SharedCreepProperties::move_by_path`] without it being converted for JS.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::Direction,
+///     local::{DirectionPath, Position},
+///     RoomCoordinate,
+/// };
+///
+/// let start = Position::new(
+///     RoomCoordinate::new(48).unwrap(),
+///     RoomCoordinate::new(10).unwrap(),
+///     "W1N1".parse().unwrap(),
+/// );
+/// let path = DirectionPath::from_directions(start, [Direction::Right, Direction::Right]);
+///
+/// // the path crosses into the room to the right
+/// assert_eq!(path.end().room_name().to_string(), "W0N1");
+/// assert_eq!(path.end().x().u8(), 0);
+///
+/// let back = path.reversed();
+/// assert_eq!(back.end(), start);
+/// assert_eq!(back.get(0), Some(Direction::Left));
+/// ```
+///
+/// [`Room::serialize_path`]: crate::objects::Room::serialize_path
+/// [`SharedCreepProperties::move_by_path`]: crate::SharedCreepProperties::move_by_path
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct DirectionPath {
+    start: Position,
+    len: usize,
+    /// Directions packed two per byte, the earlier step in the low bits.
+    packed: Vec<u8>,
+}
+
+impl DirectionPath {
+    /// Creates an empty path starting at a position.
+    #[inline]
+    pub fn new(start: Position) -> Self {
+        DirectionPath {
+            start,
+            len: 0,
+            packed: Vec::new(),
+        }
+    }
+
+    /// Creates a path from a starting position and the direction of each
+    /// step.
+    pub fn from_directions(
+        start: Position,
+        directions: impl IntoIterator<Item = Direction>,
+    ) -> Self {
+        let mut path = DirectionPath::new(start);
+        path.extend(directions);
+        path
+    }
+
+    /// Creates a path from a starting position and the position after each
+    /// step, such as from a [`LocalSearchResults`] path.
+    ///
+    /// Returns `None` if any position can't be reached in one step from the
+    /// one before it.
+    ///
+    /// [`LocalSearchResults`]: crate::local::LocalSearchResults
+    pub fn from_positions(
+        start: Position,
+        positions: impl IntoIterator<Item = Position>,
+    ) -> Option<Self> {
+        let mut path = DirectionPath::new(start);
+        let mut current = start;
+        for pos in positions {
+            let direction = current.get_direction_to(pos)?;
+            if step(current, direction) != Some(pos) {
+                return None;
+            }
+            path.push(direction);
+            current = pos;
+        }
+        Some(path)
+    }
+
+    /// The position the path starts at.
+    #[inline]
+    pub fn start(&self) -> Position {
+        self.start
+    }
+
+    /// The position the path ends at, after all of its steps.
+    pub fn end(&self) -> Position {
+        self.positions().last().unwrap_or(self.start)
+    }
+
+    /// The number of steps in the path.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the path has no steps.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Gets the direction of a step, or `None` if the index is past the end
+    /// of the path.
+    pub fn get(&self, idx: usize) -> Option<Direction> {
+        if idx >= self.len {
+            return None;
+        }
+        let byte = self.packed[idx / 2];
+        let nibble = if idx % 2 == 1 { byte >> 4 } else { byte & 0x0F };
+        Direction::from_u8(nibble)
+    }
+
+    /// Adds a step to the end of the path.
+    pub fn push(&mut self, direction: Direction) {
+        if self.len % 2 == 1 {
+            let last = self.packed.len() - 1;
+            self.packed[last] |= (direction as u8) << 4;
+        } else {
+            self.packed.push(direction as u8);
+        }
+        self.len += 1;
+    }
+
+    /// Removes the last step of the path, returning its direction.
+    pub fn pop(&mut self) -> Option<Direction> {
+        let direction = self.get(self.len.checked_sub(1)?);
+        self.truncate(self.len - 1);
+        direction
+    }
+
+    /// Shortens the path to its first `len` steps. Has no effect if the path
+    /// is already that short.
+    pub fn truncate(&mut self, len: usize) {
+        if len >= self.len {
+            return;
+        }
+        self.len = len;
+        self.packed.truncate(len.div_ceil(2));
+        if len % 2 == 1 {
+            self.packed[len / 2] &= 0x0F;
+        }
+    }
+
+    /// Creates the path leading back from the end of this path to its start.
+    pub fn reversed(&self) -> DirectionPath {
+        // a diagonal step off an edge only crosses it, so the way back comes
+        // from the positions rather than from reversing each direction
+        let mut positions: Vec<Position> = self.positions().collect();
+        positions.pop();
+        let mut reversed = DirectionPath::new(self.end());
+        let mut current = self.end();
+        for pos in positions.into_iter().rev().chain([self.start]) {
+            if let Some(direction) = current.get_direction_to(pos) {
+                reversed.push(direction);
+            }
+            current = pos;
+        }
+        reversed
+    }
+
+    /// Iterates over the direction of each step.
+    pub fn directions(&self) -> impl Iterator<Item = Direction> + '_ {
+        (0..self.len).filter_map(|idx| self.get(idx))
+    }
+
+    /// Iterates over the position after each step, not including the start.
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.directions().scan(self.start, |pos, direction| {
+            *pos = step(*pos, direction)?;
+            Some(*pos)
+        })
+    }
+
+    /// The direction a creep at `pos` should move in to follow the path,
+    /// matching the behavior of the game's `moveByPath`.
+    ///
+    /// If `pos` is on the path, this is the direction of the next step. If
+    /// not, but it's next to the first position of the path, this is the
+    /// direction toward that position. Otherwise, or if `pos` is at the end
+    /// of the path, it's `None`.
+    pub fn next_direction(&self, pos: Position) -> Option<Direction> {
+        if pos == self.start {
+            return self.get(0);
+        }
+
+        let mut positions = self.positions().enumerate();
+        if let Some((idx, _)) = positions.find(|&(_, path_pos)| path_pos == pos) {
+            return self.get(idx + 1);
+        }
+
+        let first = self.positions().next()?;
+        if pos.is_near_to(first) {
+            pos.get_direction_to(first)
+        } else {
+            None
+        }
+    }
+}
+
+/// Gets the position reached by moving one step in a direction.
+///
+/// A step off the edge of a room moves the creep onto the matching edge tile
+/// of the next room, so the part of a diagonal step along the edge is lost.
+fn step(pos: Position, direction: Direction) -> Option<Position> {
+    let (x, y) = pos.world_coords();
+    let (mut dx, mut dy): (i32, i32) = direction.into();
+    let crosses_x = (x + dx).div_euclid(50) != x.div_euclid(50);
+    let crosses_y = (y + dy).div_euclid(50) != y.div_euclid(50);
+    if crosses_x && !crosses_y {
+        dy = 0;
+    } else if crosses_y && !crosses_x {
+        dx = 0;
+    }
+    Position::checked_from_world_coords(x + dx, y + dy).ok()
+}
+
+impl Extend<Direction> for DirectionPath {
+    fn extend<T: IntoIterator<Item = Direction>>(&mut self, iter: T) {
+        for direction in iter {
+            self.push(direction);
+        }
+    }
+}
+
+impl fmt::Debug for DirectionPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DirectionPath")
+            .field("start", &self.start)
+            .field("directions", &self.directions().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+mod serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Direction, DirectionPath, FromPrimitive, Position};
+
+    #[derive(Serialize, Deserialize)]
+    struct ReadableFormat {
+        start: Position,
+        directions: String,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct BinaryFormat {
+        start: Position,
+        len: usize,
+        packed: Vec<u8>,
+    }
+
+    impl Serialize for DirectionPath {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            if serializer.is_human_readable() {
+                ReadableFormat {
+                    start: self.start,
+                    directions: self
+                        .directions()
+                        .map(|direction| char::from(b'0' + direction as u8))
+                        .collect(),
+                }
+                .serialize(serializer)
+            } else {
+                BinaryFormat {
+                    start: self.start,
+                    len: self.len,
+                    packed: self.packed.clone(),
+                }
+                .serialize(serializer)
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for DirectionPath {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            let path = if deserializer.is_human_readable() {
+                let ReadableFormat { start, directions } =
+                    ReadableFormat::deserialize(deserializer)?;
+                let mut path = DirectionPath::new(start);
+                for character in directions.chars() {
+                    let direction = character
+                        .to_digit(10)
+                        .and_then(Direction::from_u32)
+                        .ok_or_else(|| {
+                            D::Error::custom(format!(
+                                "expected direction digit 1-8, found `{character}`"
+                            ))
+                        })?;
+                    path.push(direction);
+                }
+                path
+            } else {
+                let BinaryFormat { start, len, packed } = BinaryFormat::deserialize(deserializer)?;
+                let path = DirectionPath { start, len, packed };
+                // the unused high bits of the last byte must be clear, so that
+                // equal paths have equal bytes
+                if path.packed.len() != len.div_ceil(2)
+                    || path.directions().count() != len
+                    || (len % 2 == 1 && path.packed[len / 2] & 0xF0 != 0)
+                {
+                    return Err(D::Error::custom("invalid packed directions"));
+                }
+                path
+            };
+
+            Ok(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::{pathfinder::can_step, RoomCoordinate, RoomName};
+
+    fn pos(x: u8, y: u8, room: &str) -> Position {
+        Position::new(
+            RoomCoordinate::new(x).unwrap(),
+            RoomCoordinate::new(y).unwrap(),
+            room.parse::<RoomName>().unwrap(),
+        )
+    }
+
+    /// A path crossing into the room above diagonally, then moving off the
+    /// edge before crossing back.
+    fn test_path() -> DirectionPath {
+        DirectionPath::from_directions(
+            pos(25, 1, "E1N1"),
+            [
+                Direction::Top,
+                Direction::TopRight,
+                Direction::TopRight,
+                Direction::Bottom,
+                Direction::Bottom,
+            ],
+        )
+    }
+
+    #[test]
+    fn packing() {
+        let mut path = test_path();
+        assert_eq!(path.len(), 5);
+        assert_eq!(path.packed.len(), 3);
+        assert_eq!(path.get(1), Some(Direction::TopRight));
+        assert_eq!(path.get(4), Some(Direction::Bottom));
+        assert_eq!(path.get(5), None);
+
+        assert_eq!(path.pop(), Some(Direction::Bottom));
+        path.truncate(3);
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.get(3), None);
+        path.push(Direction::Left);
+        assert_eq!(
+            path.directions().collect::<Vec<_>>(),
+            [
+                Direction::Top,
+                Direction::TopRight,
+                Direction::TopRight,
+                Direction::Left
+            ]
+        );
+
+        path.truncate(0);
+        assert!(path.is_empty());
+        assert_eq!(path.pop(), None);
+    }
+
+    #[test]
+    fn crosses_rooms() {
+        let path = test_path();
+        let positions: Vec<Position> = path.positions().collect();
+        assert_eq!(
+            positions,
+            [
+                pos(25, 0, "E1N1"),
+                // the diagonal step off the edge only crosses it
+                pos(25, 49, "E1N2"),
+                pos(26, 48, "E1N2"),
+                pos(26, 49, "E1N2"),
+                pos(26, 0, "E1N1"),
+            ]
+        );
+        // every step is one local_search could take
+        assert!([path.start()]
+            .into_iter()
+            .chain(positions.iter().copied())
+            .zip(positions.iter().copied())
+            .all(|(from, to)| can_step(from, to)));
+        assert_eq!(
+            DirectionPath::from_positions(path.start(), positions.iter().copied())
+                .unwrap()
+                .positions()
+                .collect::<Vec<_>>(),
+            positions
+        );
+        assert_eq!(
+            DirectionPath::from_positions(path.start(), [pos(27, 1, "E1N1")]),
+            None
+        );
+        // a diagonal move across the edge can't be taken in game
+        assert_eq!(
+            DirectionPath::from_positions(pos(25, 0, "E1N1"), [pos(26, 49, "E1N2")]),
+            None
+        );
+
+        let reversed = path.reversed();
+        assert_eq!(reversed.start(), path.end());
+        assert_eq!(reversed.end(), path.start());
+        let mut back: Vec<Position> = reversed.positions().collect();
+        back.pop();
+        back.reverse();
+        assert_eq!(back, positions[..positions.len() - 1]);
+        assert_eq!(
+            reversed.reversed().positions().collect::<Vec<_>>(),
+            positions
+        );
+    }
+
+    #[test]
+    fn next_direction() {
+        let path = test_path();
+        assert_eq!(path.next_direction(path.start()), Some(Direction::Top));
+        // following the path after being moved into the next room
+        let mut current = path.start();
+        for expected in path.directions() {
+            let direction = path.next_direction(current).unwrap();
+            assert_eq!(direction, expected);
+            current = step(current, direction).unwrap();
+        }
+        assert_eq!(current, path.end());
+        assert_eq!(
+            path.next_direction(pos(25, 49, "E1N2")),
+            Some(Direction::TopRight)
+        );
+        assert_eq!(path.next_direction(path.end()), None);
+        // next to the first step, but not on the path
+        assert_eq!(
+            path.next_direction(pos(24, 1, "E1N1")),
+            Some(Direction::TopRight)
+        );
+        assert_eq!(path.next_direction(pos(10, 10, "E1N1")), None);
+    }
+
+    #[test]
+    fn serde() {
+        let path = test_path();
+
+        let json = serde_json::to_string(&path).unwrap();
+        assert!(json.contains(r#""directions":"12255""#));
+        assert_eq!(serde_json::from_str::<DirectionPath>(&json).unwrap(), path);
+
+        let binary = bincode::serialize(&path).unwrap();
+        assert_eq!(
+            bincode::deserialize::<DirectionPath>(&binary).unwrap(),
+            path
+        );
+
+        let invalid = json.replace("12255", "12295");
+        assert!(serde_json::from_str::<DirectionPath>(&invalid).is_err());
+
+        // an odd number of steps leaves the last byte's high bits unused
+        let mut junk = path.clone();
+        let last = junk.packed.len() - 1;
+        junk.packed[last] |= 0x30;
+        let binary = bincode::serialize(&junk).unwrap();
+        assert!(bincode::deserialize::<DirectionPath>(&binary).is_err());
+    }
+}
@@ -0,0 +1,173 @@
+//! Distance transforms of room terrain, the distance from every tile to the
+//! closest obstacle.
+use crate::constants::{Direction, Terrain, ROOM_SIZE};
+
+use super::{LocalCostMatrix, LocalRoomTerrain, RoomXY};
+
+/// Which neighboring tiles are considered adjacent to a tile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Connectivity {
+    /// All 8 surrounding tiles are adjacent, as for creep movement. Distances
+    /// are Chebyshev distances, matching [`RoomXY::get_range_to`].
+    EightWay,
+    /// Only the 4 orthogonally neighboring tiles are adjacent. Distances are
+    /// Manhattan distances.
+    FourWay,
+}
+
+impl Connectivity {
+    /// Iterates over the directions of the tiles adjacent to a tile.
+    pub fn directions(self) -> impl Iterator<Item = Direction> {
+        Direction::iter()
+            .copied()
+            .filter(move |dir| self == Connectivity::EightWay || dir.is_orthogonal())
+    }
+}
+
+/// Computes the distance from every tile of a room to the closest wall or
+/// blocked tile.
+///
+/// Tiles with a value of 255 in `blocked` are treated as walls, matching the
+/// meaning of that value for pathfinding. Walls and blocked tiles have a
+/// distance of 0. The room edges aren't considered obstacles; if the room has
+/// no obstacles at all, every tile has a value of 255.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{distance_transform, Connectivity, LocalCostMatrix, LocalRoomTerrain, RoomXY},
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let mut blocked = LocalCostMatrix::new();
+/// blocked.set(RoomXY::checked_new(10, 10).unwrap(), 255);
+///
+/// let eight_way = distance_transform(&terrain, Some(&blocked), Connectivity::EightWay);
+/// assert_eq!(eight_way.get(RoomXY::checked_new(10, 10).unwrap()), 0);
+/// assert_eq!(eight_way.get(RoomXY::checked_new(13, 14).unwrap()), 4);
+///
+/// let four_way = distance_transform(&terrain, Some(&blocked), Connectivity::FourWay);
+/// assert_eq!(four_way.get(RoomXY::checked_new(13, 14).unwrap()), 7);
+/// ```
+pub fn distance_transform(
+    terrain: &LocalRoomTerrain,
+    blocked: Option<&LocalCostMatrix>,
+    connectivity: Connectivity,
+) -> LocalCostMatrix {
+    let mut distances = LocalCostMatrix::new_with_value(u8::MAX);
+
+    for (xy, distance) in distances.iter_mut() {
+        let is_blocked = blocked.is_some_and(|blocked| blocked.get(xy) == u8::MAX);
+        if is_blocked || terrain.get_xy(xy) == Terrain::Wall {
+            *distance = 0;
+        }
+    }
+
+    // offsets of the neighbors already visited when scanning forward, in
+    // row-major order; the backward scan uses the negated offsets
+    let offsets: &[(i8, i8)] = match connectivity {
+        Connectivity::EightWay => &[(-1, -1), (0, -1), (1, -1), (-1, 0)],
+        Connectivity::FourWay => &[(0, -1), (-1, 0)],
+    };
+
+    let forward = (0..ROOM_SIZE).flat_map(|y| (0..ROOM_SIZE).map(move |x| (x, y)));
+    for (x, y) in forward {
+        // SAFETY: both coordinates are in the range `0..ROOM_SIZE`
+        let xy = unsafe { RoomXY::unchecked_new(x, y) };
+        relax(&mut distances, xy, offsets.iter().copied());
+    }
+
+    let backward = (0..ROOM_SIZE)
+        .rev()
+        .flat_map(|y| (0..ROOM_SIZE).rev().map(move |x| (x, y)));
+    for (x, y) in backward {
+        // SAFETY: both coordinates are in the range `0..ROOM_SIZE`
+        let xy = unsafe { RoomXY::unchecked_new(x, y) };
+        relax(
+            &mut distances,
+            xy,
+            offsets.iter().map(|&(dx, dy)| (-dx, -dy)),
+        );
+    }
+
+    distances
+}
+
+/// Lowers the distance of a tile to one more than the lowest of the given
+/// neighbors.
+#[inline]
+fn relax(distances: &mut LocalCostMatrix, xy: RoomXY, offsets: impl Iterator<Item = (i8, i8)>) {
+    let closest_neighbor = offsets
+        .filter_map(|offset| xy.checked_add(offset))
+        .map(|neighbor| distances.get(neighbor))
+        .min();
+
+    if let Some(closest_neighbor) = closest_neighbor {
+        let distance = closest_neighbor.saturating_add(1);
+        if distance < distances.get(xy) {
+            distances.set(xy, distance);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{constants::ROOM_AREA, local::xy_to_terrain_index};
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    /// A room with walls along all four edges.
+    fn walled_room() -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for i in 0..ROOM_SIZE {
+            for edge in [xy(i, 0), xy(i, 49), xy(0, i), xy(49, i)] {
+                bits[xy_to_terrain_index(edge)] = 1;
+            }
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        let terrain = walled_room();
+        let mut blocked = LocalCostMatrix::new();
+        blocked.set(xy(20, 30), 255);
+        blocked.set(xy(31, 12), 255);
+        // non-255 values aren't obstacles
+        blocked.set(xy(25, 25), 10);
+
+        let obstacles: Vec<RoomXY> = blocked
+            .iter()
+            .filter(|&(xy, value)| value == 255 || terrain.get_xy(xy) == Terrain::Wall)
+            .map(|(xy, _)| xy)
+            .collect();
+
+        let eight_way = distance_transform(&terrain, Some(&blocked), Connectivity::EightWay);
+        let four_way = distance_transform(&terrain, Some(&blocked), Connectivity::FourWay);
+
+        for (tile, _) in blocked.iter() {
+            let chebyshev = obstacles.iter().map(|&o| tile.get_range_to(o)).min();
+            let manhattan = obstacles
+                .iter()
+                .map(|&o| {
+                    let (dx, dy) = tile - o;
+                    dx.unsigned_abs() + dy.unsigned_abs()
+                })
+                .min();
+            assert_eq!(Some(eight_way.get(tile)), chebyshev, "{tile}");
+            assert_eq!(Some(four_way.get(tile)), manhattan, "{tile}");
+        }
+    }
+
+    #[test]
+    fn open_room() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+        let distances = distance_transform(&terrain, None, Connectivity::EightWay);
+        assert!(distances.iter().all(|(_, distance)| distance == u8::MAX));
+    }
+}
@@ -0,0 +1,262 @@
+//! Straight lines of tiles between positions, using Bresenham's line
+//! algorithm.
+use std::iter::FusedIterator;
+
+use crate::constants::Terrain;
+
+use super::{LocalRoomTerrain, Position, RoomXY};
+
+/// An iterator over the coordinates along a line, including both ends.
+///
+/// Each coordinate is adjacent to the previous one, moving diagonally where
+/// needed, so the line has one more coordinate than the range between its
+/// ends.
+#[derive(Debug, Clone)]
+struct LineCoords {
+    x: i32,
+    y: i32,
+    end_x: i32,
+    end_y: i32,
+    dx: i32,
+    dy: i32,
+    step_x: i32,
+    step_y: i32,
+    error: i32,
+    done: bool,
+}
+
+impl LineCoords {
+    fn new((x, y): (i32, i32), (end_x, end_y): (i32, i32)) -> Self {
+        let dx = (end_x - x).abs();
+        let dy = -(end_y - y).abs();
+        LineCoords {
+            x,
+            y,
+            end_x,
+            end_y,
+            dx,
+            dy,
+            step_x: (end_x - x).signum(),
+            step_y: (end_y - y).signum(),
+            error: dx + dy,
+            done: false,
+        }
+    }
+}
+
+impl Iterator for LineCoords {
+    type Item = (i32, i32);
+
+    fn next(&mut self) -> Option<(i32, i32)> {
+        if self.done {
+            return None;
+        }
+
+        let current = (self.x, self.y);
+        if current == (self.end_x, self.end_y) {
+            self.done = true;
+            return Some(current);
+        }
+
+        let doubled_error = self.error * 2;
+        if doubled_error >= self.dy {
+            self.error += self.dy;
+            self.x += self.step_x;
+        }
+        if doubled_error <= self.dx {
+            self.error += self.dx;
+            self.y += self.step_y;
+        }
+        Some(current)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = if self.done {
+            0
+        } else {
+            // the longer axis moves on every step
+            let remaining = (self.end_x - self.x).abs().max((self.end_y - self.y).abs());
+            remaining as usize + 1
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for LineCoords {}
+
+impl FusedIterator for LineCoords {}
+
+impl RoomXY {
+    /// Iterates over the tiles along a straight line from this position to the
+    /// target, including both.
+    ///
+    /// Each tile is adjacent to the previous one, so the line is
+    /// [`RoomXY::get_range_to`] the target plus one tiles long.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomXY;
+    ///
+    /// let start = RoomXY::checked_new(10, 10).unwrap();
+    /// let target = RoomXY::checked_new(14, 12).unwrap();
+    /// let line: Vec<(u8, u8)> = start.line_to(target).map(Into::into).collect();
+    ///
+    /// assert_eq!(line, [(10, 10), (11, 11), (12, 11), (13, 12), (14, 12)]);
+    /// ```
+    pub fn line_to(self, target: RoomXY) -> impl ExactSizeIterator<Item = RoomXY> {
+        LineCoords::new(
+            (self.x.u8() as i32, self.y.u8() as i32),
+            (target.x.u8() as i32, target.y.u8() as i32),
+        )
+        .map(|(x, y)| {
+            // SAFETY: every coordinate of the line is between the ends, which
+            // are both valid coordinates.
+            unsafe { RoomXY::unchecked_new(x as u8, y as u8) }
+        })
+    }
+}
+
+impl Position {
+    /// Iterates over the positions along a straight line from this position
+    /// to the target, including both.
+    ///
+    /// The line is drawn in world coordinates, so it crosses room edges
+    /// between positions in different rooms. Each position is adjacent to
+    /// the previous one in world coordinates, so the line is
+    /// [`Position::get_range_to`] the target plus one positions long.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::{Position, RoomCoordinate, RoomName};
+    ///
+    /// let coord = |value| RoomCoordinate::new(value).unwrap();
+    /// let start = Position::new(coord(48), coord(20), "W0N1".parse().unwrap());
+    /// let target = Position::new(coord(1), coord(20), "E0N1".parse().unwrap());
+    ///
+    /// let rooms: Vec<String> = start
+    ///     .line_to(target)
+    ///     .map(|pos| pos.room_name().to_string())
+    ///     .collect();
+    /// assert_eq!(rooms, ["W0N1", "W0N1", "E0N1", "E0N1"]);
+    /// ```
+    pub fn line_to(self, target: Position) -> impl ExactSizeIterator<Item = Position> {
+        LineCoords::new(self.world_coords(), target.world_coords())
+            .map(|(x, y)| Position::from_world_coords(x, y))
+    }
+}
+
+/// Gets whether there are no walls on the tiles between two positions, along
+/// the line drawn by [`RoomXY::line_to`].
+///
+/// The tiles at both ends of the line aren't checked.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{line_of_sight, xy_to_terrain_index, LocalRoomTerrain, RoomXY},
+/// };
+///
+/// let xy = |x, y| RoomXY::checked_new(x, y).unwrap();
+///
+/// let mut bits = Box::new([0; ROOM_AREA]);
+/// bits[xy_to_terrain_index(xy(12, 10))] = 1;
+/// let terrain = LocalRoomTerrain::new_from_bits(bits);
+///
+/// assert!(!line_of_sight(&terrain, xy(10, 10), xy(15, 10)));
+/// assert!(line_of_sight(&terrain, xy(10, 10), xy(15, 15)));
+/// assert!(line_of_sight(&terrain, xy(10, 10), xy(12, 10)));
+/// ```
+pub fn line_of_sight(terrain: &LocalRoomTerrain, from: RoomXY, to: RoomXY) -> bool {
+    let mut line = from.line_to(to);
+    // skip the start, and stop before the end
+    line.next();
+    let between = line.len().saturating_sub(1);
+    line.take(between)
+        .all(|xy| terrain.get_xy(xy) != Terrain::Wall)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::{RoomCoordinate, RoomName};
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn lines_are_connected_and_symmetric_in_length() {
+        let ends = [
+            (xy(0, 0), xy(49, 49)),
+            (xy(0, 49), xy(49, 0)),
+            (xy(25, 25), xy(25, 25)),
+            (xy(3, 40), xy(30, 7)),
+            (xy(10, 10), xy(11, 30)),
+            (xy(49, 1), xy(0, 2)),
+        ];
+
+        for (start, end) in ends {
+            let line: Vec<_> = start.line_to(end).collect();
+            assert_eq!(line.len(), start.get_range_to(end) as usize + 1);
+            assert_eq!(line.first(), Some(&start));
+            assert_eq!(line.last(), Some(&end));
+            assert!(line
+                .windows(2)
+                .all(|pair| pair[0].is_near_to(pair[1]) && pair[0] != pair[1]));
+
+            let reversed: Vec<_> = end.line_to(start).collect();
+            assert_eq!(reversed.len(), line.len());
+        }
+    }
+
+    #[test]
+    fn exact_size() {
+        let mut line = xy(0, 0).line_to(xy(7, 3));
+        for remaining in (0..=8).rev() {
+            assert_eq!(line.len(), remaining);
+            line.next();
+        }
+        assert_eq!(line.next(), None);
+    }
+
+    #[test]
+    fn positions_cross_rooms() {
+        let coord = |value| RoomCoordinate::new(value).unwrap();
+        let room: RoomName = "W0N0".parse().unwrap();
+        let start = Position::new(coord(40), coord(45), room);
+        let end = Position::new(coord(2), coord(10), "E0S0".parse().unwrap());
+
+        let line: Vec<_> = start.line_to(end).collect();
+        assert_eq!(line.len(), start.get_range_to(end) as usize + 1);
+        assert_eq!(line.first(), Some(&start));
+        assert_eq!(line.last(), Some(&end));
+        assert!(line.iter().any(|pos| pos.room_name() == "W0S0"));
+        assert!(line
+            .windows(2)
+            .all(|pair| pair[0].get_range_to(pair[1]) == 1));
+    }
+
+    #[test]
+    fn line_of_sight_through_gaps() {
+        use crate::{constants::ROOM_AREA, local::xy_to_terrain_index};
+
+        // a wall along x = 20, except for a gap at y = 25
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for y in 0..50 {
+            if y != 25 {
+                bits[xy_to_terrain_index(xy(20, y))] = 1;
+            }
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        assert!(line_of_sight(&terrain, xy(15, 25), xy(25, 25)));
+        assert!(!line_of_sight(&terrain, xy(15, 24), xy(25, 24)));
+        assert!(line_of_sight(&terrain, xy(15, 24), xy(20, 24)));
+        assert!(line_of_sight(&terrain, xy(20, 24), xy(20, 24)));
+        assert!(line_of_sight(&terrain, xy(19, 24), xy(20, 25)));
+    }
+}
@@ -0,0 +1,408 @@
+//! Minimum-cut calculation separating tiles in a room from its exits, as
+//! used for placing ramparts.
+use std::collections::VecDeque;
+
+use crate::constants::{Terrain, ROOM_AREA};
+
+use super::{linear_index_to_xy, xy_to_linear_index, LocalCostMatrix, LocalRoomTerrain, RoomXY};
+
+/// Capacity of edges which can't be cut.
+const INFINITE: u64 = u32::MAX as u64;
+
+const SOURCE: usize = 2 * ROOM_AREA;
+const SINK: usize = 2 * ROOM_AREA + 1;
+
+/// Finds the set of tiles with the lowest total weight which separates all
+/// `protected` tiles from the exits of a room, such as to place ramparts on.
+///
+/// The weight of each tile is its value in `weights` if given and non-zero,
+/// otherwise 1. Tiles with a weight of 255 are never part of the cut. Walls
+/// are never part of the cut, and neither are protected tiles, exit tiles or
+/// the tiles next to exits, where structures can't be built.
+///
+/// Returns `None` if the protected tiles can't be separated from the exits,
+/// such as when one of them is next to an exit. The tiles of the cut are
+/// returned in linear index order.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{min_cut_to_exits, LocalRoomTerrain, RoomXY},
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let center = RoomXY::checked_new(25, 25).unwrap();
+///
+/// let cut = min_cut_to_exits(&terrain, [center], None).unwrap();
+/// assert_eq!(cut.len(), 8);
+/// assert!(cut.iter().all(|&xy| xy.get_range_to(center) == 1));
+/// ```
+pub fn min_cut_to_exits(
+    terrain: &LocalRoomTerrain,
+    protected: impl IntoIterator<Item = RoomXY>,
+    weights: Option<&LocalCostMatrix>,
+) -> Option<Vec<RoomXY>> {
+    let walkable = |xy: RoomXY| terrain.get_xy(xy) != Terrain::Wall;
+    let is_exit = |xy: RoomXY| xy.is_room_edge() && walkable(xy);
+
+    let mut is_protected = [false; ROOM_AREA];
+    for xy in protected {
+        if walkable(xy) {
+            is_protected[xy_to_linear_index(xy)] = true;
+        }
+    }
+
+    // each tile is split into an entry and an exit node, connected by an edge
+    // with the tile's weight; cutting that edge means building on the tile
+    let entry_node = |xy: RoomXY| 2 * xy_to_linear_index(xy);
+    let exit_node = |xy: RoomXY| 2 * xy_to_linear_index(xy) + 1;
+
+    let mut graph = FlowGraph::new(2 * ROOM_AREA + 2);
+    for (idx, &tile_protected) in is_protected.iter().enumerate() {
+        let xy = linear_index_to_xy(idx);
+        if !walkable(xy) {
+            continue;
+        }
+
+        let next_to_exit = is_exit(xy) || xy.neighbors().into_iter().any(is_exit);
+        if next_to_exit {
+            if tile_protected {
+                return None;
+            }
+            graph.add_edge(entry_node(xy), SINK, INFINITE);
+        } else if tile_protected {
+            graph.add_edge(SOURCE, exit_node(xy), INFINITE);
+        } else {
+            let weight = match weights.map_or(0, |weights| weights.get(xy)) {
+                0 => 1,
+                u8::MAX => INFINITE,
+                weight => weight as u64,
+            };
+            graph.add_edge(entry_node(xy), exit_node(xy), weight);
+        }
+
+        for neighbor in xy.neighbors() {
+            if walkable(neighbor) {
+                graph.add_edge(exit_node(xy), entry_node(neighbor), INFINITE);
+            }
+        }
+    }
+
+    if graph.max_flow(SOURCE, SINK) >= INFINITE {
+        return None;
+    }
+
+    let reachable = graph.reachable_from(SOURCE);
+    let cut = (0..ROOM_AREA)
+        .map(linear_index_to_xy)
+        .filter(|&xy| reachable[entry_node(xy)] && !reachable[exit_node(xy)])
+        .collect();
+
+    Some(cut)
+}
+
+struct FlowEdge {
+    to: usize,
+    capacity: u64,
+}
+
+/// A flow network solved with Dinic's algorithm.
+///
+/// Edges are stored in pairs, with each edge at an even index followed by its
+/// residual edge.
+struct FlowGraph {
+    edges: Vec<FlowEdge>,
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl FlowGraph {
+    fn new(nodes: usize) -> Self {
+        FlowGraph {
+            edges: Vec::new(),
+            adjacency: vec![Vec::new(); nodes],
+        }
+    }
+
+    fn add_edge(&mut self, from: usize, to: usize, capacity: u64) {
+        self.adjacency[from].push(self.edges.len());
+        self.edges.push(FlowEdge { to, capacity });
+        self.adjacency[to].push(self.edges.len());
+        self.edges.push(FlowEdge {
+            to: from,
+            capacity: 0,
+        });
+    }
+
+    /// Finds the distance of each node from the source in the residual graph,
+    /// or `usize::MAX` for unreachable nodes.
+    fn levels(&self, source: usize) -> Vec<usize> {
+        let mut levels = vec![usize::MAX; self.adjacency.len()];
+        let mut queue = VecDeque::new();
+        levels[source] = 0;
+        queue.push_back(source);
+
+        while let Some(node) = queue.pop_front() {
+            for &edge in &self.adjacency[node] {
+                let FlowEdge { to, capacity } = self.edges[edge];
+                if capacity > 0 && levels[to] == usize::MAX {
+                    levels[to] = levels[node] + 1;
+                    queue.push_back(to);
+                }
+            }
+        }
+
+        levels
+    }
+
+    fn reachable_from(&self, source: usize) -> Vec<bool> {
+        self.levels(source)
+            .into_iter()
+            .map(|level| level != usize::MAX)
+            .collect()
+    }
+
+    fn max_flow(&mut self, source: usize, sink: usize) -> u64 {
+        let mut total = 0;
+
+        loop {
+            let mut levels = self.levels(source);
+            if levels[sink] == usize::MAX {
+                return total;
+            }
+
+            // find augmenting paths along increasing levels with an iterative
+            // depth-first search, remembering which edge each node is on
+            let mut next_edge = vec![0; self.adjacency.len()];
+            let mut path: Vec<usize> = Vec::new();
+            let mut node = source;
+
+            loop {
+                if node == sink {
+                    let flow = path
+                        .iter()
+                        .map(|&edge| self.edges[edge].capacity)
+                        .min()
+                        .unwrap_or(0);
+                    for &edge in &path {
+                        self.edges[edge].capacity -= flow;
+                        self.edges[edge ^ 1].capacity += flow;
+                    }
+                    total += flow;
+                    if total >= INFINITE {
+                        return total;
+                    }
+                    path.clear();
+                    node = source;
+                    continue;
+                }
+
+                let advance = loop {
+                    let Some(&edge) = self.adjacency[node].get(next_edge[node]) else {
+                        break None;
+                    };
+                    let FlowEdge { to, capacity } = self.edges[edge];
+                    if capacity > 0 && levels[to] == levels[node] + 1 {
+                        break Some(edge);
+                    }
+                    next_edge[node] += 1;
+                };
+
+                match advance {
+                    Some(edge) => {
+                        path.push(edge);
+                        node = self.edges[edge].to;
+                    }
+                    None => {
+                        // dead end, don't visit this node again during this phase
+                        levels[node] = usize::MAX;
+                        let Some(edge) = path.pop() else {
+                            break;
+                        };
+                        node = self.edges[edge ^ 1].to;
+                        next_edge[node] += 1;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::xy_to_terrain_index;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    fn terrain_with_walls(walls: impl IntoIterator<Item = RoomXY>) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for wall in walls {
+            bits[xy_to_terrain_index(wall)] = 1;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn single_tile_in_open_room() {
+        let terrain = terrain_with_walls([]);
+        let center = xy(25, 25);
+
+        let cut = min_cut_to_exits(&terrain, [center], None).unwrap();
+        assert_eq!(cut.len(), 8);
+        assert!(cut.iter().all(|&tile| tile.get_range_to(center) == 1));
+
+        // making the inner ring expensive pushes the cut out to the next ring
+        let mut weights = LocalCostMatrix::new();
+        for tile in &cut {
+            weights.set(*tile, 10);
+        }
+        let cut = min_cut_to_exits(&terrain, [center], Some(&weights)).unwrap();
+        assert_eq!(cut.len(), 16);
+        assert!(cut.iter().all(|&tile| tile.get_range_to(center) == 2));
+    }
+
+    #[test]
+    fn corridor() {
+        // the only exits are on the right edge, and the left part of the room is
+        // closed off by a wall at x = 20 except for a corridor 3 tiles wide
+        let edges = (0..50).flat_map(|i| [xy(0, i), xy(i, 0), xy(i, 49)]);
+        let walls = (0..50).filter(|y| !(30..33).contains(y)).map(|y| xy(20, y));
+        let terrain = terrain_with_walls(edges.chain(walls));
+        let protected = || (3..18).flat_map(|x| (3..47).map(move |y| xy(x, y)));
+
+        let cut = min_cut_to_exits(&terrain, protected(), None).unwrap();
+        assert_eq!(cut, vec![xy(20, 30), xy(20, 31), xy(20, 32)]);
+
+        // in an open room, the cut surrounds the protected area
+        let open = terrain_with_walls([]);
+        let cut = min_cut_to_exits(&open, protected(), None).unwrap();
+        assert_eq!(cut.len(), 2 * 17 + 2 * 44);
+        assert!(cut
+            .iter()
+            .all(|&tile| protected().map(|p| p.get_range_to(tile)).min() == Some(1)));
+    }
+
+    /// A room layout with one row of tiles per line, `#` for walls, `~` for
+    /// swamps and `.` for plains. It has exits on all four sides and a basin
+    /// in the middle closed off by thick walls, except for an opening 3 tiles
+    /// wide at the top and 2 tiles wide on the right.
+    const BASIN_ROOM: [&str; 50] = [
+        "########.......###################################",
+        "###.#..#.......##.....#.####.#............#..#...#",
+        "#.#.#..#...............#....#............#.....#.#",
+        "#...........................~...................##",
+        "###.......................~~~~~................#.#",
+        "..........................~~~~~..................#",
+        ".........................~~~~~~~.................#",
+        "..........................~~~~~...........~......#",
+        "..........................~~~~~.........~~~~~....#",
+        "#...........................~...........~~~~~..#.#",
+        "###....................................~~~~~~~.###",
+        "###.....................................~~~~~..###",
+        "##......................................~~~~~..#.#",
+        "#.#.......................................~....###",
+        "#.#..............................................#",
+        "#..............#########...#########...........###",
+        "###............#########...#########............##",
+        "##.............##.................##............##",
+        "#.#............##.................##.............#",
+        "#..............##.................##...........#.#",
+        "#..............##..........~~~~...##..............",
+        "#..............##..........~~~~...##..............",
+        "#..............##..........~~~~...##..............",
+        "#..............##..........~~~~...##..............",
+        "#..............##.................##..............",
+        "#..............##.................##..............",
+        "#.......~......##.................##..............",
+        "###...~~~~~....##.................##.............#",
+        "#....~~~~~~~...##.................##.............#",
+        "##...~~~~~~~...##.................##.............#",
+        "#...~~~~~~~~~..##..............................#.#",
+        "##...~~~~~~~...##................................#",
+        "#....~~~~~~~...##.................##............##",
+        "###...~~~~~....##.................##.............#",
+        "##......~......#####################.............#",
+        "#..............#####################...........###",
+        "#................................................#",
+        "#.......................................#.......##",
+        "###...................~...............#####....#.#",
+        "##...................~~~..............#####....#.#",
+        "#......#............~~~~~............#######.....#",
+        "#.....###............~~~..............#####......#",
+        "#.#..#####............~...............#####.....##",
+        "##....###...............................#......#.#",
+        "#......#.......................................#.#",
+        "#..............................................#.#",
+        "###..............................................#",
+        "#...............#...##.###...#...........#..###.##",
+        "#..##..#.......##...##...##..#...........##.######",
+        "##############################...........#########",
+    ];
+
+    fn terrain_from_rows(rows: &[&str; 50]) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for (y, row) in rows.iter().enumerate() {
+            for (x, tile) in row.bytes().enumerate() {
+                bits[xy_to_terrain_index(xy(x as u8, y as u8))] = match tile {
+                    b'#' => 1,
+                    b'~' => 2,
+                    _ => 0,
+                };
+            }
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn basin_room() {
+        let terrain = terrain_from_rows(&BASIN_ROOM);
+        assert_eq!(terrain.get_xy(xy(8, 0)), Terrain::Plain);
+        assert_eq!(terrain.get_xy(xy(16, 15)), Terrain::Wall);
+        assert_eq!(terrain.get_xy(xy(28, 20)), Terrain::Swamp);
+
+        let protected: Vec<RoomXY> = (20..=30)
+            .flat_map(|x| (20..=30).map(move |y| xy(x, y)))
+            .collect();
+        let cut = min_cut_to_exits(&terrain, protected.iter().copied(), None).unwrap();
+
+        // the narrowest cut closes both openings in the basin's walls
+        assert_eq!(cut.len(), 5);
+        assert!(cut.iter().all(|tile| {
+            let (x, y) = (tile.x.u8(), tile.y.u8());
+            ((24..=26).contains(&x) && (15..=16).contains(&y))
+                || ((34..=35).contains(&x) && (30..=31).contains(&y))
+        }));
+
+        // nothing outside the cut connects the protected tiles to an exit
+        let mut reached = [false; ROOM_AREA];
+        let mut open: Vec<RoomXY> = protected;
+        for &tile in &open {
+            reached[xy_to_linear_index(tile)] = true;
+        }
+        while let Some(tile) = open.pop() {
+            assert!(!tile.is_room_edge(), "reached exit at {tile}");
+            for next in tile.neighbors() {
+                let idx = xy_to_linear_index(next);
+                if !reached[idx] && terrain.get_xy(next) != Terrain::Wall && !cut.contains(&next) {
+                    reached[idx] = true;
+                    open.push(next);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn protected_next_to_exit() {
+        let terrain = terrain_with_walls([]);
+        assert_eq!(min_cut_to_exits(&terrain, [xy(1, 25)], None), None);
+
+        // unless the exit is walled off
+        let terrain = terrain_with_walls((0..50).map(|y| xy(0, y)));
+        let cut = min_cut_to_exits(&terrain, [xy(1, 25)], None).unwrap();
+        assert_eq!(cut.len(), 5);
+    }
+}
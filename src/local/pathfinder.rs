@@ -0,0 +1,784 @@
+//! Pathfinding over [`LocalRoomTerrain`] and [`LocalCostMatrix`], performed
+//! entirely in Rust memory.
+//!
+//! This is a reimplementation of the multi-room A* search done by the game's
+//! [`PathFinder`], without calling into JavaScript. Terrain is read from a
+//! [`LocalRoomTerrain`] provided by the caller for each room, and costs from a
+//! per-room callback returning a [`LocalCostMatrix`], so no data crosses the
+//! wasm boundary during the search and it can run in native tests.
+//!
+//! [`PathFinder`]: crate::pathfinder::PathFinder
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    constants::{Direction, Terrain, ROOM_AREA},
+    pathfinder::SearchGoal,
+};
+
+use super::{xy_to_linear_index, LocalCostMatrix, LocalRoomTerrain, Position, RoomName, RoomXY};
+
+mod distance_field;
+mod flow_field;
+
+pub use self::{distance_field::*, flow_field::*};
+
+/// Cost matrix value which marks a tile as unwalkable.
+const IMPASSABLE: u8 = u8::MAX;
+
+/// The result of the room callback of a [`LocalSearchOptions`], determining
+/// the costs used for a given room.
+#[derive(Clone, Debug, Default)]
+pub enum LocalRoomCostResult {
+    /// Use the given matrix; values of 0 fall back to the terrain cost, and
+    /// 255 marks a tile as unwalkable.
+    CostMatrix(Box<LocalCostMatrix>),
+    /// Avoid pathing through this room entirely.
+    Impassable,
+    /// Use only the terrain costs for this room.
+    #[default]
+    Default,
+}
+
+/// Options for a call to [`local_search`] or [`local_search_many`], mirroring
+/// [`SearchOptions`].
+///
+/// [`SearchOptions`]: crate::pathfinder::SearchOptions
+pub struct LocalSearchOptions<F>
+where
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    callback: F,
+    plain_cost: u8,
+    swamp_cost: u8,
+    flee: bool,
+    max_ops: u32,
+    max_rooms: u8,
+    max_cost: f64,
+    heuristic_weight: f64,
+}
+
+impl Default for LocalSearchOptions<fn(RoomName) -> LocalRoomCostResult> {
+    fn default() -> Self {
+        fn cost_matrix(_: RoomName) -> LocalRoomCostResult {
+            LocalRoomCostResult::Default
+        }
+
+        LocalSearchOptions::new(cost_matrix)
+    }
+}
+
+impl<F> LocalSearchOptions<F>
+where
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    /// Creates options with the given room callback and the default costs
+    /// and limits: plain cost `1`, swamp cost `5`, no flee, `2000` ops, `16`
+    /// rooms, no maximum cost and heuristic weight `1.2`.
+    #[inline]
+    pub fn new(callback: F) -> Self {
+        LocalSearchOptions {
+            callback,
+            plain_cost: 1,
+            swamp_cost: 5,
+            flee: false,
+            max_ops: 2000,
+            max_rooms: 16,
+            max_cost: f64::INFINITY,
+            heuristic_weight: 1.2,
+        }
+    }
+
+    /// Sets the room callback, which gives the costs to use for each room.
+    pub fn room_callback<F2>(self, callback: F2) -> LocalSearchOptions<F2>
+    where
+        F2: FnMut(RoomName) -> LocalRoomCostResult,
+    {
+        LocalSearchOptions {
+            callback,
+            plain_cost: self.plain_cost,
+            swamp_cost: self.swamp_cost,
+            flee: self.flee,
+            max_ops: self.max_ops,
+            max_rooms: self.max_rooms,
+            max_cost: self.max_cost,
+            heuristic_weight: self.heuristic_weight,
+        }
+    }
+
+    /// Sets plain cost - default `1`.
+    #[inline]
+    pub fn plain_cost(mut self, cost: u8) -> Self {
+        self.plain_cost = cost;
+        self
+    }
+
+    /// Sets swamp cost - default `5`.
+    #[inline]
+    pub fn swamp_cost(mut self, cost: u8) -> Self {
+        self.swamp_cost = cost;
+        self
+    }
+
+    /// Sets whether this is a flee search - default `false`.
+    ///
+    /// A flee search finds a path to the closest tile which is at least each
+    /// goal's range away from that goal, instead of one in range of a goal.
+    #[inline]
+    pub fn flee(mut self, flee: bool) -> Self {
+        self.flee = flee;
+        self
+    }
+
+    /// Sets maximum ops - default `2000`.
+    #[inline]
+    pub fn max_ops(mut self, ops: u32) -> Self {
+        self.max_ops = ops;
+        self
+    }
+
+    /// Sets maximum rooms - default `16`, max `64`.
+    #[inline]
+    pub fn max_rooms(mut self, rooms: u8) -> Self {
+        self.max_rooms = rooms.min(64);
+        self
+    }
+
+    /// Sets maximum path cost - default `f64::Infinity`.
+    #[inline]
+    pub fn max_cost(mut self, cost: f64) -> Self {
+        self.max_cost = cost;
+        self
+    }
+
+    /// Sets heuristic weight - default `1.2`.
+    #[inline]
+    pub fn heuristic_weight(mut self, weight: f64) -> Self {
+        self.heuristic_weight = weight;
+        self
+    }
+}
+
+/// The results of a [`local_search`] or [`local_search_many`], mirroring
+/// [`SearchResults`].
+///
+/// [`SearchResults`]: crate::pathfinder::SearchResults
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LocalSearchResults {
+    path: Vec<Position>,
+    ops: u32,
+    cost: u32,
+    incomplete: bool,
+}
+
+impl LocalSearchResults {
+    /// The path that was found, not including the origin. May be incomplete.
+    pub fn path(&self) -> &[Position] {
+        &self.path
+    }
+
+    /// Consumes the results, returning the path that was found.
+    pub fn into_path(self) -> Vec<Position> {
+        self.path
+    }
+
+    /// The number of operations the pathfinding operation performed.
+    pub fn ops(&self) -> u32 {
+        self.ops
+    }
+
+    /// Total cost of all tiles used in the path.
+    pub fn cost(&self) -> u32 {
+        self.cost
+    }
+
+    /// Whether this search failed to find a complete path.
+    pub fn incomplete(&self) -> bool {
+        self.incomplete
+    }
+}
+
+/// Search for a path from an origin to a goal position, stopping once in
+/// `range` of it.
+///
+/// The `terrain` callback provides the terrain for each room the search
+/// enters; rooms for which it returns `None` are not pathed through.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{local_search, LocalRoomCostResult, LocalRoomTerrain, LocalSearchOptions, Position},
+///     RoomCoordinate, RoomName,
+/// };
+///
+/// let room: RoomName = "W1N1".parse().unwrap();
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let pos = |x, y| {
+///     Position::new(
+///         RoomCoordinate::new(x).unwrap(),
+///         RoomCoordinate::new(y).unwrap(),
+///         room,
+///     )
+/// };
+///
+/// let results = local_search(
+///     pos(10, 10),
+///     pos(20, 15),
+///     1,
+///     |_| Some(&terrain),
+///     None::<LocalSearchOptions<fn(RoomName) -> LocalRoomCostResult>>,
+/// );
+///
+/// assert!(!results.incomplete());
+/// assert_eq!(results.path().len(), 9);
+/// assert_eq!(results.path().last().unwrap().get_range_to(pos(20, 15)), 1);
+/// ```
+pub fn local_search<'t, T, F>(
+    from: Position,
+    to: Position,
+    range: u32,
+    terrain: T,
+    options: Option<LocalSearchOptions<F>>,
+) -> LocalSearchResults
+where
+    T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    local_search_many(
+        from,
+        std::iter::once(SearchGoal::new(to, range)),
+        terrain,
+        options,
+    )
+}
+
+/// Search for a path from an origin to the closest of a number of goals,
+/// each with their own range.
+///
+/// See [`local_search`].
+pub fn local_search_many<'t, T, F>(
+    from: Position,
+    to: impl Iterator<Item = SearchGoal>,
+    terrain: T,
+    options: Option<LocalSearchOptions<F>>,
+) -> LocalSearchResults
+where
+    T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    let goals: Vec<(Position, u32)> = to.map(|goal| (goal.pos, goal.range)).collect();
+
+    match options {
+        Some(options) => Search::new(terrain, options).run(from, &goals),
+        None => Search::new(terrain, LocalSearchOptions::default()).run(from, &goals),
+    }
+}
+
+/// Per-room state of a search.
+struct SearchRoom<'t> {
+    terrain: &'t LocalRoomTerrain,
+    costs: Option<Box<LocalCostMatrix>>,
+    /// Best known path cost to each tile, indexed by linear index.
+    g: Box<[u32; ROOM_AREA]>,
+    /// Direction taken to reach each tile, `None` if unreached or the origin.
+    parent: Box<[Option<Direction>; ROOM_AREA]>,
+    closed: Box<[bool; ROOM_AREA]>,
+}
+
+/// An entry of the open set, ordered so that a max-heap pops the lowest
+/// estimated total cost first, then the lowest heuristic.
+#[derive(PartialEq, Eq)]
+struct OpenNode {
+    f: u32,
+    h: u32,
+    g: u32,
+    pos: Position,
+}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        Reverse((self.f, self.h, self.g, self.pos))
+            .cmp(&Reverse((other.f, other.h, other.g, other.pos)))
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+struct Search<'t, T, F>
+where
+    T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    terrain: T,
+    options: LocalSearchOptions<F>,
+    rooms: HashMap<RoomName, SearchRoom<'t>>,
+    blocked_rooms: HashSet<RoomName>,
+}
+
+impl<'t, T, F> Search<'t, T, F>
+where
+    T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+    F: FnMut(RoomName) -> LocalRoomCostResult,
+{
+    fn new(terrain: T, options: LocalSearchOptions<F>) -> Self {
+        Search {
+            terrain,
+            options,
+            rooms: HashMap::new(),
+            blocked_rooms: HashSet::new(),
+        }
+    }
+
+    /// Loads a room into the search, returning whether it can be pathed
+    /// through.
+    fn load_room(&mut self, room_name: RoomName) -> bool {
+        if self.rooms.contains_key(&room_name) {
+            return true;
+        }
+        if self.blocked_rooms.contains(&room_name)
+            || self.rooms.len() >= self.options.max_rooms as usize
+        {
+            return false;
+        }
+
+        let costs = match (self.options.callback)(room_name) {
+            LocalRoomCostResult::CostMatrix(costs) => Some(costs),
+            LocalRoomCostResult::Default => None,
+            LocalRoomCostResult::Impassable => {
+                self.blocked_rooms.insert(room_name);
+                return false;
+            }
+        };
+        let Some(terrain) = (self.terrain)(room_name) else {
+            self.blocked_rooms.insert(room_name);
+            return false;
+        };
+
+        self.rooms.insert(
+            room_name,
+            SearchRoom {
+                terrain,
+                costs,
+                g: Box::new([u32::MAX; ROOM_AREA]),
+                parent: Box::new([None; ROOM_AREA]),
+                closed: Box::new([false; ROOM_AREA]),
+            },
+        );
+        true
+    }
+
+    /// The cost of stepping onto a tile, or `None` if it's unwalkable.
+    fn tile_cost(&self, pos: Position) -> Option<u32> {
+        let room = self.rooms.get(&pos.room_name())?;
+
+        tile_cost(
+            room.terrain,
+            room.costs.as_deref(),
+            pos.xy(),
+            self.options.plain_cost,
+            self.options.swamp_cost,
+        )
+    }
+
+    fn room_mut(&mut self, pos: Position) -> &mut SearchRoom<'t> {
+        self.rooms
+            .get_mut(&pos.room_name())
+            .expect("expected room to be loaded")
+    }
+
+    fn run(mut self, from: Position, goals: &[(Position, u32)]) -> LocalSearchResults {
+        let flee = self.options.flee;
+        let heuristic = |pos: Position| -> u32 {
+            let distances_to_goals = goals.iter().map(|&(goal, range)| {
+                if flee {
+                    range.saturating_sub(pos.get_range_to(goal))
+                } else {
+                    pos.get_range_to(goal).saturating_sub(range)
+                }
+            });
+            if flee {
+                distances_to_goals.max().unwrap_or(0)
+            } else {
+                distances_to_goals.min().unwrap_or(0)
+            }
+        };
+        let weight = self.options.heuristic_weight;
+        let estimate = |g: u32, h: u32| g.saturating_add((h as f64 * weight) as u32);
+
+        if !self.load_room(from.room_name()) {
+            return LocalSearchResults {
+                path: Vec::new(),
+                ops: 0,
+                cost: 0,
+                incomplete: heuristic(from) != 0,
+            };
+        }
+
+        self.room_mut(from).g[xy_to_linear_index(from.xy())] = 0;
+
+        let mut open = BinaryHeap::new();
+        let start_h = heuristic(from);
+        open.push(OpenNode {
+            f: estimate(0, start_h),
+            h: start_h,
+            g: 0,
+            pos: from,
+        });
+
+        let mut best = (start_h, 0, from);
+        let mut ops = 0;
+        let mut found = false;
+
+        while let Some(OpenNode { h, g, pos, .. }) = open.pop() {
+            let idx = xy_to_linear_index(pos.xy());
+            {
+                let room = self.room_mut(pos);
+                if room.closed[idx] || room.g[idx] < g {
+                    continue;
+                }
+                room.closed[idx] = true;
+            }
+
+            if (h, g) < (best.0, best.1) {
+                best = (h, g, pos);
+            }
+            if h == 0 {
+                found = true;
+                break;
+            }
+            if ops >= self.options.max_ops {
+                break;
+            }
+            ops += 1;
+
+            for &dir in Direction::iter() {
+                let Ok(next) = pos.checked_add_direction(dir) else {
+                    continue;
+                };
+                if !can_step(pos, next) || !self.load_room(next.room_name()) {
+                    continue;
+                }
+                let Some(cost) = self.tile_cost(next) else {
+                    continue;
+                };
+
+                let next_g = g + cost;
+                if next_g as f64 > self.options.max_cost {
+                    continue;
+                }
+
+                let next_idx = xy_to_linear_index(next.xy());
+                let room = self.room_mut(next);
+                if room.closed[next_idx] || room.g[next_idx] <= next_g {
+                    continue;
+                }
+                room.g[next_idx] = next_g;
+                room.parent[next_idx] = Some(dir);
+
+                let next_h = heuristic(next);
+                open.push(OpenNode {
+                    f: estimate(next_g, next_h),
+                    h: next_h,
+                    g: next_g,
+                    pos: next,
+                });
+            }
+        }
+
+        let (_, cost, end) = best;
+        let mut path = Vec::new();
+        let mut current = end;
+        while let Some(dir) =
+            self.rooms[&current.room_name()].parent[xy_to_linear_index(current.xy())]
+        {
+            path.push(current);
+            current = current + <(i32, i32)>::from(-dir);
+        }
+        path.reverse();
+
+        LocalSearchResults {
+            path,
+            ops,
+            cost,
+            incomplete: !found,
+        }
+    }
+}
+
+/// The cost of stepping onto a tile, or `None` if it's unwalkable.
+///
+/// Non-zero values in the cost matrix take precedence over the terrain, with
+/// 255 marking the tile as unwalkable.
+fn tile_cost(
+    terrain: &LocalRoomTerrain,
+    costs: Option<&LocalCostMatrix>,
+    xy: RoomXY,
+    plain_cost: u8,
+    swamp_cost: u8,
+) -> Option<u32> {
+    if let Some(costs) = costs {
+        match costs.get(xy) {
+            0 => (),
+            IMPASSABLE => return None,
+            cost => return Some(cost as u32),
+        }
+    }
+
+    match terrain.get_xy(xy) {
+        Terrain::Plain => Some(plain_cost as u32),
+        Terrain::Swamp => Some(swamp_cost as u32),
+        Terrain::Wall => None,
+    }
+}
+
+/// Whether a step between two adjacent positions can be part of a path.
+///
+/// Creeps standing on an exit tile get moved straight across the border to
+/// the matching tile of the next room, so paths can't travel along the edge
+/// of a room or cross into another room diagonally.
+pub(crate) fn can_step(from: Position, to: Position) -> bool {
+    if from.room_name() != to.room_name() {
+        let (from_x, from_y) = from.world_coords();
+        let (to_x, to_y) = to.world_coords();
+        return from_x == to_x || from_y == to_y;
+    }
+    can_step_in_room(from.xy(), to.xy())
+}
+
+/// Whether a step between two adjacent tiles of the same room can be part of
+/// a path, which isn't the case for steps along the edge of the room.
+pub(crate) fn can_step_in_room(from: RoomXY, to: RoomXY) -> bool {
+    !(from.is_room_edge() && to.is_room_edge())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{constants::ROOM_AREA, local::xy_to_terrain_index, RoomXY};
+
+    fn pos(x: u8, y: u8, room: &str) -> Position {
+        let xy = RoomXY::checked_new(x, y).unwrap();
+        Position::new(xy.x, xy.y, room.parse().unwrap())
+    }
+
+    fn terrain_with_walls(walls: impl IntoIterator<Item = (u8, u8)>) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for (x, y) in walls {
+            bits[xy_to_terrain_index(RoomXY::checked_new(x, y).unwrap())] = 1;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    type DefaultOptions = LocalSearchOptions<fn(RoomName) -> LocalRoomCostResult>;
+
+    fn default_options() -> Option<DefaultOptions> {
+        None
+    }
+
+    fn assert_contiguous(from: Position, path: &[Position]) {
+        let mut previous = from;
+        for &step in path {
+            assert_eq!(previous.get_range_to(step), 1, "{previous} -> {step}");
+            previous = step;
+        }
+    }
+
+    #[test]
+    fn straight_path() {
+        let terrain = terrain_with_walls([]);
+        let results = local_search(
+            pos(10, 10, "W1N1"),
+            pos(20, 10, "W1N1"),
+            0,
+            |_| Some(&terrain),
+            default_options(),
+        );
+
+        assert!(!results.incomplete());
+        assert_eq!(results.path().len(), 10);
+        assert_eq!(results.cost(), 10);
+        assert_eq!(results.path().last(), Some(&pos(20, 10, "W1N1")));
+        assert_contiguous(pos(10, 10, "W1N1"), results.path());
+    }
+
+    #[test]
+    fn paths_around_walls_and_costs() {
+        // a wall at x = 15 with a single gap at y = 30
+        let terrain = terrain_with_walls((0..50).filter(|&y| y != 30).map(|y| (15, y)));
+        let results = local_search(
+            pos(10, 10, "W1N1"),
+            pos(20, 10, "W1N1"),
+            0,
+            |_| Some(&terrain),
+            default_options(),
+        );
+
+        assert!(!results.incomplete());
+        assert!(results.path().contains(&pos(15, 30, "W1N1")));
+
+        // blocking the gap with the cost matrix makes the goal unreachable
+        let mut costs = LocalCostMatrix::new();
+        costs.set(RoomXY::checked_new(15, 30).unwrap(), 255);
+        let results = local_search(
+            pos(10, 10, "W1N1"),
+            pos(20, 10, "W1N1"),
+            0,
+            |_| Some(&terrain),
+            Some(
+                LocalSearchOptions::new(|_| {
+                    LocalRoomCostResult::CostMatrix(Box::new(costs.clone()))
+                })
+                .max_ops(10_000),
+            ),
+        );
+
+        assert!(results.incomplete());
+        assert!(results.path().iter().all(|p| p.x().u8() < 15));
+    }
+
+    #[test]
+    fn multiple_goals_with_range() {
+        let terrain = terrain_with_walls([]);
+        let goals = vec![
+            SearchGoal::new(pos(40, 10, "W1N1"), 3),
+            SearchGoal::new(pos(10, 20, "W1N1"), 5),
+        ];
+        let results = local_search_many(
+            pos(10, 10, "W1N1"),
+            goals.into_iter(),
+            |_| Some(&terrain),
+            default_options(),
+        );
+
+        assert!(!results.incomplete());
+        assert_eq!(results.path().len(), 5);
+        assert_eq!(
+            results
+                .path()
+                .last()
+                .unwrap()
+                .get_range_to(pos(10, 20, "W1N1")),
+            5
+        );
+    }
+
+    #[test]
+    fn crosses_rooms() {
+        let terrain = terrain_with_walls([]);
+        let results = local_search(
+            pos(45, 25, "W1N1"),
+            pos(5, 25, "W0N1"),
+            0,
+            |_| Some(&terrain),
+            default_options(),
+        );
+
+        assert!(!results.incomplete());
+        assert_eq!(results.path().len(), 10);
+        assert_eq!(results.path().last(), Some(&pos(5, 25, "W0N1")));
+        assert_contiguous(pos(45, 25, "W1N1"), results.path());
+
+        // blocking the destination room leaves the search incomplete
+        let results = local_search(
+            pos(45, 25, "W1N1"),
+            pos(5, 25, "W0N1"),
+            0,
+            |room| (room != "W0N1").then_some(&terrain),
+            default_options(),
+        );
+        assert!(results.incomplete());
+
+        let results = local_search(
+            pos(45, 25, "W1N1"),
+            pos(5, 25, "W0N1"),
+            0,
+            |_| Some(&terrain),
+            Some(LocalSearchOptions::default().max_rooms(1)),
+        );
+        assert!(results.incomplete());
+    }
+
+    #[test]
+    fn crosses_rooms_orthogonally() {
+        assert!(can_step(pos(49, 20, "W1N1"), pos(0, 20, "W0N1")));
+        assert!(!can_step(pos(49, 20, "W1N1"), pos(0, 21, "W0N1")));
+        assert!(!can_step(pos(20, 0, "W1N1"), pos(19, 49, "W1N2")));
+        assert!(!can_step(pos(49, 20, "W1N1"), pos(49, 21, "W1N1")));
+        assert!(can_step(pos(48, 20, "W1N1"), pos(49, 21, "W1N1")));
+
+        let terrain = terrain_with_walls([]);
+        let from = pos(45, 20, "W1N1");
+        let results = local_search(
+            from,
+            pos(5, 30, "W0N1"),
+            0,
+            |_| Some(&terrain),
+            default_options(),
+        );
+
+        assert!(!results.incomplete());
+        // a diagonal path would take 10 steps, but the border is crossed
+        // with a straight step
+        assert_eq!(results.path().len(), 11);
+        assert_contiguous(from, results.path());
+        let mut previous = from;
+        for &step in results.path() {
+            assert!(can_step(previous, step), "{previous} -> {step}");
+            previous = step;
+        }
+    }
+
+    #[test]
+    fn flee() {
+        let terrain = terrain_with_walls([]);
+        let threats = vec![
+            SearchGoal::new(pos(25, 25, "W1N1"), 5),
+            SearchGoal::new(pos(28, 25, "W1N1"), 5),
+        ];
+        let results = local_search_many(
+            pos(26, 26, "W1N1"),
+            threats.into_iter(),
+            |_| Some(&terrain),
+            Some(LocalSearchOptions::default().flee(true)),
+        );
+
+        assert!(!results.incomplete());
+        let end = *results.path().last().unwrap();
+        assert!(end.get_range_to(pos(25, 25, "W1N1")) >= 5);
+        assert!(end.get_range_to(pos(28, 25, "W1N1")) >= 5);
+        assert_eq!(results.path().len(), 4);
+        assert_contiguous(pos(26, 26, "W1N1"), results.path());
+    }
+
+    #[test]
+    fn limits() {
+        let terrain = terrain_with_walls([]);
+        let results = local_search(
+            pos(1, 1, "W1N1"),
+            pos(48, 48, "W1N1"),
+            0,
+            |_| Some(&terrain),
+            Some(LocalSearchOptions::default().max_ops(5)),
+        );
+        assert!(results.incomplete());
+        assert_eq!(results.ops(), 5);
+
+        let results = local_search(
+            pos(1, 1, "W1N1"),
+            pos(48, 48, "W1N1"),
+            0,
+            |_| Some(&terrain),
+            Some(LocalSearchOptions::default().max_cost(10.0)),
+        );
+        assert!(results.incomplete());
+        assert!(results.cost() <= 10);
+    }
+}
@@ -0,0 +1,437 @@
+//! Distance fields from many sources at once, computed with Dijkstra's
+//! algorithm over [`LocalRoomTerrain`] and [`LocalCostMatrix`].
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    constants::{Direction, ROOM_AREA},
+    local::{
+        linear_index_to_xy, xy_to_linear_index, LocalCostMatrix, LocalRoomTerrain, Position,
+        RoomName, RoomXY,
+    },
+};
+
+use super::{can_step, can_step_in_room, tile_cost, LocalRoomCostResult, LocalSearchOptions};
+
+/// Marker for tiles which haven't been reached.
+const UNREACHED: u32 = u32::MAX;
+
+/// The path cost from the closest of a set of sources to every tile of a
+/// room.
+///
+/// Each step follows the same rules as [`local_search`], so steps along the
+/// edge of the room aren't allowed, and costs the same: the value in the
+/// cost matrix if it's non-zero, otherwise the terrain's cost. Source
+/// tiles always have a distance of 0, even if they're not walkable, so that
+/// objects like sources and controllers can be used directly.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{LocalRoomTerrain, RoomDistanceField, RoomXY},
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let threats = [
+///     RoomXY::checked_new(10, 10).unwrap(),
+///     RoomXY::checked_new(30, 10).unwrap(),
+/// ];
+///
+/// let field = RoomDistanceField::new(threats, &terrain, None);
+///
+/// assert_eq!(field.get(RoomXY::checked_new(10, 15).unwrap()), Some(5));
+/// assert_eq!(field.get(RoomXY::checked_new(25, 10).unwrap()), Some(5));
+/// // only the bottom row is at least 39 tiles from both threats
+/// assert_eq!(field.tiles_at_least(39).count(), 50);
+/// ```
+///
+/// [`local_search`]: crate::local::local_search
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomDistanceField {
+    /// Distance to each tile, indexed by linear index.
+    distances: Box<[u32; ROOM_AREA]>,
+}
+
+impl RoomDistanceField {
+    /// Computes the distance field for a room from a set of sources, with the
+    /// default terrain costs of `1` for plains and `5` for swamps.
+    pub fn new(
+        sources: impl IntoIterator<Item = RoomXY>,
+        terrain: &LocalRoomTerrain,
+        costs: Option<&LocalCostMatrix>,
+    ) -> Self {
+        Self::new_with_terrain_costs(sources, terrain, costs, 1, 5)
+    }
+
+    /// Computes the distance field for a room from a set of sources, with
+    /// custom terrain costs.
+    pub fn new_with_terrain_costs(
+        sources: impl IntoIterator<Item = RoomXY>,
+        terrain: &LocalRoomTerrain,
+        costs: Option<&LocalCostMatrix>,
+        plain_cost: u8,
+        swamp_cost: u8,
+    ) -> Self {
+        let mut distances = Box::new([UNREACHED; ROOM_AREA]);
+        let mut open = BinaryHeap::new();
+
+        for source in sources {
+            distances[xy_to_linear_index(source)] = 0;
+            open.push(Reverse((0, source)));
+        }
+
+        while let Some(Reverse((distance, xy))) = open.pop() {
+            if distances[xy_to_linear_index(xy)] < distance {
+                continue;
+            }
+
+            for &dir in Direction::iter() {
+                let Some(next) = xy.checked_add_direction(dir) else {
+                    continue;
+                };
+                if !can_step_in_room(xy, next) {
+                    continue;
+                }
+                let Some(cost) = tile_cost(terrain, costs, next, plain_cost, swamp_cost) else {
+                    continue;
+                };
+
+                let next_distance = distance + cost;
+                let entry = &mut distances[xy_to_linear_index(next)];
+                if *entry > next_distance {
+                    *entry = next_distance;
+                    open.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+
+        RoomDistanceField { distances }
+    }
+
+    /// Gets the distance to a tile, or `None` if it can't be reached from any
+    /// source.
+    #[inline]
+    pub fn get(&self, xy: RoomXY) -> Option<u32> {
+        match self.distances[xy_to_linear_index(xy)] {
+            UNREACHED => None,
+            distance => Some(distance),
+        }
+    }
+
+    /// Iterates over the distance to each tile, in linear index order.
+    pub fn iter(&self) -> impl Iterator<Item = (RoomXY, Option<u32>)> + '_ {
+        self.distances.iter().enumerate().map(|(idx, &distance)| {
+            (
+                linear_index_to_xy(idx),
+                (distance != UNREACHED).then_some(distance),
+            )
+        })
+    }
+
+    /// Iterates over the reachable tiles which are at least `distance` away
+    /// from every source.
+    ///
+    /// With threats as the sources, these are the tiles a creep can flee to.
+    pub fn tiles_at_least(&self, distance: u32) -> impl Iterator<Item = RoomXY> + '_ {
+        self.iter().filter_map(move |(xy, value)| match value {
+            Some(value) if value >= distance => Some(xy),
+            _ => None,
+        })
+    }
+}
+
+/// The path cost from the closest of a set of sources to every reachable tile
+/// in a set of rooms.
+///
+/// This follows the same rules as [`local_search_many`], with room terrain
+/// provided by the `terrain` callback and costs by the room callback of the
+/// options. Of the options, the terrain costs, `max_rooms` and `max_cost` are
+/// used; tiles further than `max_cost` are left unreached.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{LocalRoomTerrain, LocalSearchOptions, MultiRoomDistanceField, Position},
+///     RoomCoordinate,
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let source = Position::new(
+///     RoomCoordinate::new(45).unwrap(),
+///     RoomCoordinate::new(25).unwrap(),
+///     "W1N1".parse().unwrap(),
+/// );
+///
+/// let field = MultiRoomDistanceField::new(
+///     [source],
+///     |_| Some(&terrain),
+///     Some(LocalSearchOptions::default().max_rooms(2)),
+/// );
+///
+/// assert_eq!(field.get(source + (10, 0)), Some(10));
+/// assert_eq!(field.rooms().count(), 2);
+/// ```
+///
+/// [`local_search_many`]: crate::local::local_search_many
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultiRoomDistanceField {
+    rooms: HashMap<RoomName, RoomDistanceField>,
+}
+
+impl MultiRoomDistanceField {
+    /// Computes the distance field from a set of sources, loading rooms as
+    /// they're reached. Without options, the defaults of
+    /// [`LocalSearchOptions`] are used.
+    pub fn new<'t, T, F>(
+        sources: impl IntoIterator<Item = Position>,
+        mut terrain: T,
+        options: Option<LocalSearchOptions<F>>,
+    ) -> Self
+    where
+        T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+        F: FnMut(RoomName) -> LocalRoomCostResult,
+    {
+        match options {
+            Some(options) => Self::new_with_options(sources, &mut terrain, options),
+            None => Self::new_with_options(sources, &mut terrain, LocalSearchOptions::default()),
+        }
+    }
+
+    fn new_with_options<'t, T, F>(
+        sources: impl IntoIterator<Item = Position>,
+        terrain: &mut T,
+        mut options: LocalSearchOptions<F>,
+    ) -> Self
+    where
+        T: FnMut(RoomName) -> Option<&'t LocalRoomTerrain>,
+        F: FnMut(RoomName) -> LocalRoomCostResult,
+    {
+        struct FieldRoom<'t> {
+            terrain: &'t LocalRoomTerrain,
+            costs: Option<Box<LocalCostMatrix>>,
+            distances: Box<[u32; ROOM_AREA]>,
+        }
+
+        let mut rooms: HashMap<RoomName, FieldRoom<'t>> = HashMap::new();
+        let mut blocked_rooms = HashSet::new();
+
+        // loads a room, returning whether it can be pathed through
+        let max_rooms = options.max_rooms as usize;
+        let mut load_room = |rooms: &mut HashMap<RoomName, FieldRoom<'t>>, room_name| {
+            if rooms.contains_key(&room_name) {
+                return true;
+            }
+            if blocked_rooms.contains(&room_name) || rooms.len() >= max_rooms {
+                return false;
+            }
+            let costs = match (options.callback)(room_name) {
+                LocalRoomCostResult::CostMatrix(costs) => Some(costs),
+                LocalRoomCostResult::Default => None,
+                LocalRoomCostResult::Impassable => {
+                    blocked_rooms.insert(room_name);
+                    return false;
+                }
+            };
+            let Some(terrain) = terrain(room_name) else {
+                blocked_rooms.insert(room_name);
+                return false;
+            };
+            rooms.insert(
+                room_name,
+                FieldRoom {
+                    terrain,
+                    costs,
+                    distances: Box::new([UNREACHED; ROOM_AREA]),
+                },
+            );
+            true
+        };
+
+        let mut open = BinaryHeap::new();
+        for source in sources {
+            if load_room(&mut rooms, source.room_name()) {
+                let room = rooms.get_mut(&source.room_name()).unwrap();
+                room.distances[xy_to_linear_index(source.xy())] = 0;
+                open.push(Reverse((0, source)));
+            }
+        }
+
+        while let Some(Reverse((distance, pos))) = open.pop() {
+            if rooms[&pos.room_name()].distances[xy_to_linear_index(pos.xy())] < distance {
+                continue;
+            }
+
+            for &dir in Direction::iter() {
+                let Ok(next) = pos.checked_add_direction(dir) else {
+                    continue;
+                };
+                if !can_step(pos, next) || !load_room(&mut rooms, next.room_name()) {
+                    continue;
+                }
+
+                let room = rooms.get_mut(&next.room_name()).unwrap();
+                let Some(cost) = tile_cost(
+                    room.terrain,
+                    room.costs.as_deref(),
+                    next.xy(),
+                    options.plain_cost,
+                    options.swamp_cost,
+                ) else {
+                    continue;
+                };
+
+                let next_distance = distance + cost;
+                if next_distance as f64 > options.max_cost {
+                    continue;
+                }
+                let entry = &mut room.distances[xy_to_linear_index(next.xy())];
+                if *entry > next_distance {
+                    *entry = next_distance;
+                    open.push(Reverse((next_distance, next)));
+                }
+            }
+        }
+
+        MultiRoomDistanceField {
+            rooms: rooms
+                .into_iter()
+                .map(|(room_name, room)| {
+                    (
+                        room_name,
+                        RoomDistanceField {
+                            distances: room.distances,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Gets the distance to a position, or `None` if it can't be reached from
+    /// any source.
+    pub fn get(&self, pos: Position) -> Option<u32> {
+        self.rooms
+            .get(&pos.room_name())
+            .and_then(|room| room.get(pos.xy()))
+    }
+
+    /// Gets the distance field of a single room, if it was searched.
+    pub fn room(&self, room_name: RoomName) -> Option<&RoomDistanceField> {
+        self.rooms.get(&room_name)
+    }
+
+    /// Iterates over the names of the rooms that were searched.
+    pub fn rooms(&self) -> impl Iterator<Item = RoomName> + '_ {
+        self.rooms.keys().copied()
+    }
+
+    /// Iterates over the reachable positions which are at least `distance`
+    /// away from every source.
+    pub fn positions_at_least(&self, distance: u32) -> impl Iterator<Item = Position> + '_ {
+        self.rooms.iter().flat_map(move |(&room_name, room)| {
+            room.tiles_at_least(distance)
+                .map(move |xy| Position::new(xy.x, xy.y, room_name))
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::xy_to_terrain_index;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn walls_swamps_and_costs() {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        // a wall across the room at y = 20 except for a swamp at x = 25
+        for x in 0..50 {
+            bits[xy_to_terrain_index(xy(x, 20))] = if x == 25 { 2 } else { 1 };
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let field = RoomDistanceField::new([xy(25, 10)], &terrain, None);
+        assert_eq!(field.get(xy(25, 10)), Some(0));
+        assert_eq!(field.get(xy(25, 19)), Some(9));
+        assert_eq!(field.get(xy(25, 20)), Some(14));
+        assert_eq!(field.get(xy(25, 21)), Some(15));
+        assert_eq!(field.get(xy(0, 20)), None);
+
+        let mut costs = LocalCostMatrix::new();
+        costs.set(xy(25, 20), 255);
+        let field = RoomDistanceField::new([xy(25, 10)], &terrain, Some(&costs));
+        assert_eq!(field.get(xy(25, 21)), None);
+        assert_eq!(field.tiles_at_least(0).count(), 50 * 20);
+    }
+
+    #[test]
+    fn closest_source_wins() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+        let field = RoomDistanceField::new([xy(0, 0), xy(49, 49)], &terrain, None);
+
+        assert_eq!(field.get(xy(10, 10)), Some(10));
+        assert_eq!(field.get(xy(40, 40)), Some(9));
+        assert_eq!(field.iter().filter(|(_, d)| d.is_none()).count(), 0);
+    }
+
+    #[test]
+    fn no_steps_along_edges() {
+        // walls next to an edge tile leave only steps along the edge
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for y in 9..=11 {
+            bits[xy_to_terrain_index(xy(1, y))] = 1;
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        let field = RoomDistanceField::new([xy(0, 10)], &terrain, None);
+        assert_eq!(field.get(xy(0, 10)), Some(0));
+        assert_eq!(field.get(xy(0, 9)), None);
+        assert_eq!(field.tiles_at_least(0).count(), 1);
+
+        // edge tiles are still reached from inside the room
+        let field = RoomDistanceField::new([xy(1, 20)], &terrain, None);
+        assert_eq!(field.get(xy(0, 21)), Some(1));
+        assert_eq!(field.get(xy(0, 22)), Some(2));
+    }
+
+    #[test]
+    fn multi_room() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+        let room_name: RoomName = "E5S5".parse().unwrap();
+        let source = Position::new(xy(25, 25).x, xy(25, 25).y, room_name);
+
+        let field = MultiRoomDistanceField::new(
+            [source],
+            |_| Some(&terrain),
+            Some(
+                LocalSearchOptions::new(|room| {
+                    if room == "E5S4" {
+                        LocalRoomCostResult::Impassable
+                    } else {
+                        LocalRoomCostResult::Default
+                    }
+                })
+                .max_cost(40.0),
+            ),
+        );
+
+        assert_eq!(field.get(source), Some(0));
+        assert_eq!(field.get(source + (40, 0)), Some(40));
+        assert_eq!(field.get(source + (41, 0)), None);
+        assert_eq!(field.get(source + (0, -30)), None);
+        assert!(field.room("E5S4".parse().unwrap()).is_none());
+        assert!(field.rooms().all(|room| room != "E5S4"));
+        assert!(field
+            .positions_at_least(30)
+            .all(|pos| pos.room_name() != room_name));
+    }
+}
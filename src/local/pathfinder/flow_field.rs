@@ -0,0 +1,191 @@
+//! Flow fields, giving the direction to move in from every tile of a room to
+//! reach the closest of a set of goals.
+use std::iter;
+
+use num_traits::FromPrimitive;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::Direction,
+    local::{LocalCostMatrix, LocalRoomTerrain, Position, RoomName, RoomXY},
+};
+
+use super::RoomDistanceField;
+
+/// The direction to move in from each tile of a room to reach the closest of
+/// a set of goals.
+///
+/// Following the directions from any tile takes the cheapest path to a goal,
+/// with costs following the same rules as [`RoomDistanceField`]. Computing a
+/// flow field once lets any number of creeps heading to the same goals share
+/// it, instead of each searching for its own path.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Direction, ROOM_AREA},
+///     local::{FlowField, LocalRoomTerrain, RoomXY},
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+/// let storage = RoomXY::checked_new(25, 25).unwrap();
+///
+/// let field = FlowField::new("W1N1".parse().unwrap(), [storage], &terrain, None);
+///
+/// assert_eq!(
+///     field.get(RoomXY::checked_new(25, 24).unwrap()),
+///     Some(Direction::Bottom)
+/// );
+/// assert_eq!(field.get(storage), None);
+/// assert_eq!(field.path_from(RoomXY::checked_new(20, 20).unwrap()).count(), 5);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FlowField {
+    room_name: RoomName,
+    /// The direction to move in from each tile, or 0 where there's none.
+    directions: LocalCostMatrix,
+}
+
+impl FlowField {
+    /// Computes the flow field for a room toward a set of goals, with the
+    /// default terrain costs of `1` for plains and `5` for swamps.
+    pub fn new(
+        room_name: RoomName,
+        goals: impl IntoIterator<Item = RoomXY>,
+        terrain: &LocalRoomTerrain,
+        costs: Option<&LocalCostMatrix>,
+    ) -> Self {
+        let distances = RoomDistanceField::new(goals, terrain, costs);
+        Self::from_distance_field(room_name, &distances)
+    }
+
+    /// Computes the flow field for a room from a distance field, moving from
+    /// each tile to the neighbor closest to the distance field's sources.
+    pub fn from_distance_field(room_name: RoomName, distances: &RoomDistanceField) -> Self {
+        let mut directions = LocalCostMatrix::new();
+
+        for (xy, distance) in distances.iter() {
+            // goals and unreachable tiles have no direction
+            if !matches!(distance, Some(distance) if distance > 0) {
+                continue;
+            }
+
+            let closest = Direction::iter()
+                .filter_map(|&dir| {
+                    let neighbor = xy.checked_add_direction(dir)?;
+                    Some((distances.get(neighbor)?, dir))
+                })
+                .min_by_key(|&(distance, _)| distance);
+
+            if let Some((_, dir)) = closest {
+                directions.set(xy, dir as u8);
+            }
+        }
+
+        FlowField {
+            room_name,
+            directions,
+        }
+    }
+
+    /// Gets the name of the room this flow field is for.
+    #[inline]
+    pub fn room_name(&self) -> RoomName {
+        self.room_name
+    }
+
+    /// Gets the direction to move in from a tile, or `None` if the tile is a
+    /// goal or can't reach any goal.
+    #[inline]
+    pub fn get(&self, xy: RoomXY) -> Option<Direction> {
+        Direction::from_u8(self.directions.get(xy))
+    }
+
+    /// Gets the direction to move in from a position, or `None` if the
+    /// position is a goal, can't reach any goal, or is in another room.
+    #[inline]
+    pub fn get_pos(&self, pos: Position) -> Option<Direction> {
+        if pos.room_name() != self.room_name {
+            return None;
+        }
+        self.get(pos.xy())
+    }
+
+    /// Iterates over the tiles of the path from a tile to its closest goal,
+    /// not including the starting tile.
+    ///
+    /// The path is empty if the tile is a goal or can't reach any goal.
+    pub fn path_from(&self, xy: RoomXY) -> impl Iterator<Item = RoomXY> + '_ {
+        iter::successors(Some(xy), |&xy| {
+            self.get(xy).and_then(|dir| xy.checked_add_direction(dir))
+        })
+        .skip(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        constants::{Terrain, ROOM_AREA},
+        local::xy_to_terrain_index,
+    };
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn follows_cheapest_paths() {
+        // a wall at x = 20 with a single gap at y = 40
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for y in 0..50 {
+            if y != 40 {
+                bits[xy_to_terrain_index(xy(20, y))] = 1;
+            }
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+        let goals = [xy(10, 10), xy(10, 30)];
+
+        let distances = RoomDistanceField::new(goals, &terrain, None);
+        let field = FlowField::from_distance_field("W1N1".parse().unwrap(), &distances);
+
+        for (tile, distance) in distances.iter() {
+            let path: Vec<RoomXY> = field.path_from(tile).collect();
+            match distance {
+                Some(distance) => {
+                    // every step costs 1 in a room of plains
+                    assert_eq!(path.len() as u32, distance, "{tile}");
+                    assert!(goals.contains(path.last().unwrap_or(&tile)), "{tile}");
+                    assert!(path
+                        .iter()
+                        .all(|&step| terrain.get_xy(step) != Terrain::Wall));
+                }
+                None => assert!(path.is_empty()),
+            }
+        }
+
+        // tiles right of the wall all route through the gap
+        let path: Vec<RoomXY> = field.path_from(xy(30, 10)).collect();
+        assert!(path.contains(&xy(20, 40)));
+    }
+
+    #[test]
+    fn positions_and_serde() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+        let room_name = "E5S5".parse().unwrap();
+        let field = FlowField::new(room_name, [xy(10, 10)], &terrain, None);
+
+        let tile = xy(10, 12);
+        let pos = Position::new(tile.x, tile.y, room_name);
+        assert_eq!(field.get_pos(pos), Some(Direction::Top));
+        let elsewhere = Position::new(pos.x(), pos.y(), "E5S6".parse().unwrap());
+        assert_eq!(field.get_pos(elsewhere), None);
+
+        let serialized = bincode::serialize(&field).unwrap();
+        let round_trip: FlowField = bincode::deserialize(&serialized).unwrap();
+        assert_eq!(round_trip, field);
+    }
+}
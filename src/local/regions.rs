@@ -0,0 +1,244 @@
+//! Labeling of the connected walkable regions of a room.
+use std::collections::VecDeque;
+
+use crate::constants::{Terrain, ROOM_AREA};
+
+use super::{
+    linear_index_to_xy, xy_to_linear_index, Connectivity, LocalCostMatrix, LocalRoomTerrain, RoomXY,
+};
+
+/// Marker for tiles which aren't part of any region.
+const UNLABELED: u16 = u16::MAX;
+
+/// The connected regions of walkable tiles in a room.
+///
+/// Walls, and tiles with a value of 255 in the blocking matrix, aren't part of
+/// any region. Regions are labeled from 0 in the order of their first tile by
+/// linear index.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ROOM_AREA,
+///     local::{Connectivity, LocalCostMatrix, LocalRoomTerrain, RoomRegions, RoomXY},
+/// };
+///
+/// let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+///
+/// // ramparts in a ring around the center of the room
+/// let center = RoomXY::checked_new(25, 25).unwrap();
+/// let mut blocked = LocalCostMatrix::new();
+/// for xy in center.neighbors() {
+///     blocked.set(xy, 255);
+/// }
+///
+/// let regions = RoomRegions::new(&terrain, Some(&blocked), Connectivity::EightWay);
+/// assert_eq!(regions.len(), 2);
+///
+/// let pocket = regions.label(center).unwrap();
+/// assert_eq!(regions.size(pocket), 1);
+/// assert!(regions.is_enclosed(pocket));
+/// assert_eq!(regions.border(pocket).len(), 8);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RoomRegions {
+    /// Label of each tile, indexed by linear index.
+    labels: Box<[u16; ROOM_AREA]>,
+    sizes: Vec<usize>,
+    connectivity: Connectivity,
+}
+
+impl RoomRegions {
+    /// Labels the connected regions of a room.
+    ///
+    /// Tiles with a value of 255 in `blocked` are treated as walls, matching
+    /// the meaning of that value for pathfinding.
+    pub fn new(
+        terrain: &LocalRoomTerrain,
+        blocked: Option<&LocalCostMatrix>,
+        connectivity: Connectivity,
+    ) -> Self {
+        let is_walkable = |xy: RoomXY| {
+            let is_blocked = blocked.is_some_and(|blocked| blocked.get(xy) == u8::MAX);
+            !is_blocked && terrain.get_xy(xy) != Terrain::Wall
+        };
+
+        let mut labels = Box::new([UNLABELED; ROOM_AREA]);
+        let mut sizes = Vec::new();
+        let mut queue = VecDeque::new();
+
+        for idx in 0..ROOM_AREA {
+            let start = linear_index_to_xy(idx);
+            if labels[idx] != UNLABELED || !is_walkable(start) {
+                continue;
+            }
+
+            let label = sizes.len() as u16;
+            let mut size = 0;
+            labels[idx] = label;
+            queue.push_back(start);
+
+            while let Some(xy) = queue.pop_front() {
+                size += 1;
+                for dir in connectivity.directions() {
+                    let Some(next) = xy.checked_add_direction(dir) else {
+                        continue;
+                    };
+                    let entry = &mut labels[xy_to_linear_index(next)];
+                    if *entry == UNLABELED && is_walkable(next) {
+                        *entry = label;
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            sizes.push(size);
+        }
+
+        RoomRegions {
+            labels,
+            sizes,
+            connectivity,
+        }
+    }
+
+    /// Gets the label of the region a tile is in, or `None` if the tile is a
+    /// wall or blocked.
+    #[inline]
+    pub fn label(&self, xy: RoomXY) -> Option<usize> {
+        match self.labels[xy_to_linear_index(xy)] {
+            UNLABELED => None,
+            label => Some(label as usize),
+        }
+    }
+
+    /// The number of regions in the room.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Whether the room has no walkable tiles at all.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.sizes.is_empty()
+    }
+
+    /// The number of tiles in each region, indexed by label.
+    #[inline]
+    pub fn sizes(&self) -> &[usize] {
+        &self.sizes
+    }
+
+    /// The number of tiles in a region.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `label` isn't a label of this room's regions.
+    #[inline]
+    pub fn size(&self, label: usize) -> usize {
+        self.sizes[label]
+    }
+
+    /// Whether two tiles are in the same region.
+    pub fn connected(&self, a: RoomXY, b: RoomXY) -> bool {
+        matches!((self.label(a), self.label(b)), (Some(a), Some(b)) if a == b)
+    }
+
+    /// Iterates over the tiles of a region, in linear index order.
+    pub fn tiles(&self, label: usize) -> impl Iterator<Item = RoomXY> + '_ {
+        self.labels
+            .iter()
+            .enumerate()
+            .filter(move |&(_, &tile_label)| tile_label as usize == label)
+            .map(|(idx, _)| linear_index_to_xy(idx))
+    }
+
+    /// Finds the wall and blocked tiles bordering a region, those adjacent to
+    /// any of its tiles, in linear index order.
+    pub fn border(&self, label: usize) -> Vec<RoomXY> {
+        let mut is_border = [false; ROOM_AREA];
+        for xy in self.tiles(label) {
+            for dir in self.connectivity.directions() {
+                if let Some(next) = xy.checked_add_direction(dir) {
+                    if self.label(next).is_none() {
+                        is_border[xy_to_linear_index(next)] = true;
+                    }
+                }
+            }
+        }
+
+        (0..ROOM_AREA)
+            .filter(|&idx| is_border[idx])
+            .map(linear_index_to_xy)
+            .collect()
+    }
+
+    /// Whether a region is enclosed, not reaching any tile on the edge of the
+    /// room.
+    pub fn is_enclosed(&self, label: usize) -> bool {
+        !self.tiles(label).any(|xy| xy.is_room_edge())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::xy_to_terrain_index;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn diagonal_gaps() {
+        // a diagonal line of walls across the room
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for i in 0..50 {
+            bits[xy_to_terrain_index(xy(i, i))] = 1;
+        }
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+
+        // with 8-way movement, the line can be crossed diagonally
+        let eight_way = RoomRegions::new(&terrain, None, Connectivity::EightWay);
+        assert_eq!(eight_way.len(), 1);
+        assert_eq!(eight_way.size(0), ROOM_AREA - 50);
+        assert!(eight_way.connected(xy(10, 0), xy(0, 10)));
+        assert_eq!(eight_way.label(xy(5, 5)), None);
+
+        // but with 4-way movement it divides the room
+        let four_way = RoomRegions::new(&terrain, None, Connectivity::FourWay);
+        assert_eq!(four_way.len(), 2);
+        assert_eq!(four_way.sizes(), &[1225, 1225]);
+        assert!(!four_way.connected(xy(10, 0), xy(0, 10)));
+        assert!(!four_way.is_enclosed(0));
+        assert_eq!(four_way.border(0).len(), 50);
+        assert_eq!(four_way.tiles(1).count(), four_way.size(1));
+    }
+
+    #[test]
+    fn enclosed_pockets() {
+        let terrain = LocalRoomTerrain::new_from_bits(Box::new([0; ROOM_AREA]));
+
+        // a 5x5 box of ramparts with its corners missing
+        let mut blocked = LocalCostMatrix::new();
+        for i in 11..14 {
+            for edge in [xy(i, 10), xy(i, 14), xy(10, i), xy(14, i)] {
+                blocked.set(edge, 255);
+            }
+        }
+
+        let four_way = RoomRegions::new(&terrain, Some(&blocked), Connectivity::FourWay);
+        assert_eq!(four_way.len(), 2);
+        let inside = four_way.label(xy(12, 12)).unwrap();
+        assert!(four_way.is_enclosed(inside));
+        assert_eq!(four_way.size(inside), 9);
+        assert_eq!(four_way.border(inside).len(), 12);
+
+        // the missing corners let creeps out diagonally
+        let eight_way = RoomRegions::new(&terrain, Some(&blocked), Connectivity::EightWay);
+        assert_eq!(eight_way.len(), 1);
+        assert!(!eight_way.is_enclosed(0));
+    }
+}
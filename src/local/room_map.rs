@@ -0,0 +1,287 @@
+//! Maps of a value for each tile of a room.
+use std::ops::{Index, IndexMut};
+
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::constants::ROOM_AREA;
+
+use super::{linear_index_to_xy, xy_to_linear_index, LocalCostMatrix, Position, RoomXY};
+
+/// A value for each tile of a room, stored in Rust memory.
+///
+/// This is a generalization of [`LocalCostMatrix`] to any type of value, such
+/// as `u16` or `u32` distances, `f32` scores or `Option<ObjectId<_>>` lookups.
+/// Values are stored in the same order as [`LocalCostMatrix`], using
+/// [`xy_to_linear_index`], and iterated over in that order.
+///
+/// # Example
+///
+/// ```
+/// use screeps::local::{RoomMap, RoomXY};
+///
+/// let center = RoomXY::checked_new(25, 25).unwrap();
+/// let distances: RoomMap<u16> = RoomMap::from_fn(|xy| xy.get_range_to(center).into());
+///
+/// let weights = RoomMap::new_with_value(0.5_f32);
+/// let scores = distances.zip_with(&weights, |_, &distance, &weight| distance as f32 * weight);
+///
+/// assert_eq!(scores[RoomXY::checked_new(0, 30).unwrap()], 12.5);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RoomMap<T> {
+    values: Box<[T; ROOM_AREA]>,
+}
+
+impl<T: Clone + Default> Default for RoomMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Default> RoomMap<T> {
+    /// Creates a `RoomMap` with the default value of `T` for all positions.
+    #[inline]
+    pub fn new() -> Self {
+        Self::new_with_value(T::default())
+    }
+}
+
+impl<T: Clone> RoomMap<T> {
+    /// Creates a `RoomMap` with the same value for all positions.
+    pub fn new_with_value(value: T) -> Self {
+        let values = vec![value; ROOM_AREA]
+            .into_boxed_slice()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("expected vec to have ROOM_AREA values"));
+        RoomMap { values }
+    }
+
+    /// Sets every position to the same value.
+    pub fn fill(&mut self, value: T) {
+        self.values.fill(value);
+    }
+}
+
+impl<T> RoomMap<T> {
+    /// Creates a `RoomMap` by calling a function with each position.
+    pub fn from_fn(mut f: impl FnMut(RoomXY) -> T) -> Self {
+        let values = (0..ROOM_AREA)
+            .map(|idx| f(linear_index_to_xy(idx)))
+            .collect::<Box<[T]>>()
+            .try_into()
+            .unwrap_or_else(|_| unreachable!("expected iterator to have ROOM_AREA values"));
+        RoomMap { values }
+    }
+
+    /// Gets a reference to the value at a position.
+    #[inline]
+    pub fn get(&self, xy: RoomXY) -> &T {
+        &self[xy]
+    }
+
+    /// Gets a mutable reference to the value at a position.
+    #[inline]
+    pub fn get_mut(&mut self, xy: RoomXY) -> &mut T {
+        &mut self[xy]
+    }
+
+    /// Sets the value at a position.
+    #[inline]
+    pub fn set(&mut self, xy: RoomXY, value: T) {
+        self[xy] = value;
+    }
+
+    /// Gets the values of all positions, in linear index order.
+    pub fn values(&self) -> &[T; ROOM_AREA] {
+        &self.values
+    }
+
+    /// Iterates over each position and its value, in linear index order.
+    pub fn iter(&self) -> impl Iterator<Item = (RoomXY, &T)> {
+        self.values
+            .iter()
+            .enumerate()
+            .map(|(idx, value)| (linear_index_to_xy(idx), value))
+    }
+
+    /// Iterates over each position and a mutable reference to its value, in
+    /// linear index order.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (RoomXY, &mut T)> {
+        self.values
+            .iter_mut()
+            .enumerate()
+            .map(|(idx, value)| (linear_index_to_xy(idx), value))
+    }
+
+    /// Creates a new `RoomMap` by calling a function with each position and
+    /// its value.
+    pub fn map<U>(&self, mut f: impl FnMut(RoomXY, &T) -> U) -> RoomMap<U> {
+        RoomMap::from_fn(|xy| f(xy, &self[xy]))
+    }
+
+    /// Creates a new `RoomMap` by calling a function with each position and
+    /// its values in this and another `RoomMap`.
+    pub fn zip_with<U, V>(
+        &self,
+        other: &RoomMap<U>,
+        mut f: impl FnMut(RoomXY, &T, &U) -> V,
+    ) -> RoomMap<V> {
+        RoomMap::from_fn(|xy| f(xy, &self[xy], &other[xy]))
+    }
+}
+
+impl<T> Index<RoomXY> for RoomMap<T> {
+    type Output = T;
+
+    fn index(&self, xy: RoomXY) -> &Self::Output {
+        // SAFETY: RoomXY is always a valid coordinate.
+        unsafe { self.values.get_unchecked(xy_to_linear_index(xy)) }
+    }
+}
+
+impl<T> IndexMut<RoomXY> for RoomMap<T> {
+    fn index_mut(&mut self, xy: RoomXY) -> &mut Self::Output {
+        // SAFETY: RoomXY is always a valid coordinate.
+        unsafe { self.values.get_unchecked_mut(xy_to_linear_index(xy)) }
+    }
+}
+
+impl<T> Index<Position> for RoomMap<T> {
+    type Output = T;
+
+    fn index(&self, idx: Position) -> &Self::Output {
+        &self[RoomXY::from(idx)]
+    }
+}
+
+impl<T> IndexMut<Position> for RoomMap<T> {
+    fn index_mut(&mut self, idx: Position) -> &mut Self::Output {
+        &mut self[RoomXY::from(idx)]
+    }
+}
+
+impl From<&LocalCostMatrix> for RoomMap<u8> {
+    fn from(matrix: &LocalCostMatrix) -> Self {
+        RoomMap {
+            values: Box::new(*matrix.get_bits()),
+        }
+    }
+}
+
+impl From<LocalCostMatrix> for RoomMap<u8> {
+    fn from(matrix: LocalCostMatrix) -> Self {
+        RoomMap::from(&matrix)
+    }
+}
+
+impl From<&RoomMap<u8>> for LocalCostMatrix {
+    fn from(map: &RoomMap<u8>) -> Self {
+        let mut matrix = LocalCostMatrix::new();
+        for (xy, &value) in map.iter() {
+            matrix.set(xy, value);
+        }
+        matrix
+    }
+}
+
+impl From<RoomMap<u8>> for LocalCostMatrix {
+    fn from(map: RoomMap<u8>) -> Self {
+        LocalCostMatrix::from(&map)
+    }
+}
+
+// serialized as a sequence of the values in linear index order, checking that
+// there's exactly ROOM_AREA of them
+impl<T: Serialize> Serialize for RoomMap<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.values.iter())
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for RoomMap<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let values: Vec<T> = Vec::deserialize(deserializer)?;
+        let len = values.len();
+        let values = values.into_boxed_slice().try_into().map_err(|_| {
+            D::Error::invalid_length(len, &format!("a vec of length {ROOM_AREA}").as_str())
+        })?;
+        Ok(RoomMap { values })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn index_map_and_fill() {
+        let mut map: RoomMap<Option<u32>> = RoomMap::new();
+        assert!(map.iter().all(|(_, value)| value.is_none()));
+
+        map[xy(3, 4)] = Some(7);
+        map.set(xy(49, 0), Some(1));
+        *map.get_mut(xy(0, 49)) = Some(2);
+        assert_eq!(map.get(xy(3, 4)), &Some(7));
+        assert_eq!(map.iter().filter(|(_, value)| value.is_some()).count(), 3);
+
+        let doubled = map.map(|_, value| value.map_or(0, |value| value * 2));
+        assert_eq!(doubled[xy(3, 4)], 14);
+        assert_eq!(doubled[xy(4, 3)], 0);
+
+        for (xy, value) in map.iter_mut() {
+            if xy.x.u8() == 49 {
+                *value = None;
+            }
+        }
+        assert_eq!(map[xy(49, 0)], None);
+
+        map.fill(Some(5));
+        assert!(map.values().iter().all(|&value| value == Some(5)));
+    }
+
+    #[test]
+    fn iterates_in_linear_index_order() {
+        let map = RoomMap::from_fn(xy_to_linear_index);
+        assert!(map.iter().all(|(xy, &idx)| xy_to_linear_index(xy) == idx));
+        assert!(map
+            .iter()
+            .enumerate()
+            .all(|(idx, (_, &value))| value == idx));
+    }
+
+    #[test]
+    fn cost_matrix_conversions() {
+        let mut matrix = LocalCostMatrix::new();
+        matrix.set(xy(10, 20), 255);
+        matrix.set(xy(20, 10), 3);
+
+        let map = RoomMap::from(&matrix);
+        assert_eq!(map[xy(10, 20)], 255);
+        assert_eq!(map[xy(20, 10)], 3);
+        assert_eq!(map[xy(10, 10)], 0);
+        assert_eq!(LocalCostMatrix::from(map), matrix);
+    }
+
+    #[test]
+    fn serde() {
+        let map = RoomMap::from_fn(|xy| xy.x.u8() as f32 / 2.0 + xy.y.u8() as f32);
+
+        let json = serde_json::to_string(&map).unwrap();
+        assert_eq!(serde_json::from_str::<RoomMap<f32>>(&json).unwrap(), map);
+
+        let binary = bincode::serialize(&map).unwrap();
+        assert_eq!(bincode::deserialize::<RoomMap<f32>>(&binary).unwrap(), map);
+
+        assert!(serde_json::from_str::<RoomMap<f32>>("[1.0, 2.0]").is_err());
+    }
+}
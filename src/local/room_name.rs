@@ -12,7 +12,7 @@ use wasm_bindgen::{JsCast, JsValue};
 
 use crate::prelude::*;
 
-use super::{HALF_WORLD_SIZE, VALID_ROOM_NAME_COORDINATES};
+use super::{area::ring_offsets, HALF_WORLD_SIZE, VALID_ROOM_NAME_COORDINATES};
 
 /// A structure representing a room name.
 ///
@@ -191,6 +191,273 @@ impl RoomName {
         write!(res, "{self}").expect("expected ArrayString write to be infallible");
         res
     }
+
+    /// Gets the kind of room this is, based on its position within its
+    /// sector.
+    ///
+    /// Sectors are the 10x10 areas of rooms bordered by highways, with rooms
+    /// numbered `1` to `9` along each axis inside the sector and highways at
+    /// the multiples of 10. The same layout applies on both sides of the
+    /// `W0`/`E0` and `N0`/`S0` boundaries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::{RoomKind, RoomName};
+    ///
+    /// let kind = |name: &str| RoomName::new(name).unwrap().kind();
+    ///
+    /// assert_eq!(kind("W10N3"), RoomKind::Highway);
+    /// assert_eq!(kind("E0S20"), RoomKind::Crossroad);
+    /// assert_eq!(kind("W4N6"), RoomKind::SourceKeeper);
+    /// assert_eq!(kind("E15S35"), RoomKind::SectorCenter);
+    /// assert_eq!(kind("W3N7"), RoomKind::Normal);
+    /// ```
+    pub fn kind(&self) -> RoomKind {
+        let x = sector_offset(self.x_coord());
+        let y = sector_offset(self.y_coord());
+
+        match (x, y) {
+            (0, 0) => RoomKind::Crossroad,
+            (0, _) | (_, 0) => RoomKind::Highway,
+            (5, 5) => RoomKind::SectorCenter,
+            (4..=6, 4..=6) => RoomKind::SourceKeeper,
+            _ => RoomKind::Normal,
+        }
+    }
+
+    /// Whether this room is a highway room, including the crossroads where
+    /// highways meet.
+    #[inline]
+    pub fn is_highway(&self) -> bool {
+        matches!(self.kind(), RoomKind::Highway | RoomKind::Crossroad)
+    }
+
+    /// Whether this room is a crossroad, where a horizontal and a vertical
+    /// highway meet.
+    #[inline]
+    pub fn is_crossroad(&self) -> bool {
+        self.kind() == RoomKind::Crossroad
+    }
+
+    /// Whether this room is one of the 8 source keeper rooms surrounding the
+    /// center of its sector.
+    #[inline]
+    pub fn is_source_keeper_room(&self) -> bool {
+        self.kind() == RoomKind::SourceKeeper
+    }
+
+    /// Whether this room is the center room of its sector.
+    #[inline]
+    pub fn is_sector_center(&self) -> bool {
+        self.kind() == RoomKind::SectorCenter
+    }
+
+    /// Gets the center room of the sector this room is in.
+    ///
+    /// Highway rooms are on the border of two or more sectors; they're
+    /// considered part of the sector with the same tens digit, so `W10N3` is
+    /// in the sector centered at `W15N5` and `E0S0` in the one centered at
+    /// `E5S5`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomName;
+    ///
+    /// let sector = |name: &str| RoomName::new(name).unwrap().sector().to_string();
+    ///
+    /// assert_eq!(sector("W3N7"), "W5N5");
+    /// assert_eq!(sector("E21S39"), "E25S35");
+    /// assert_eq!(sector("W10N3"), "W15N5");
+    /// ```
+    pub fn sector(&self) -> RoomName {
+        let center = |coord: i32| {
+            // work with the displayed room number, so the W and N sides
+            // mirror the E and S sides
+            let number = if coord < 0 { -coord - 1 } else { coord };
+            let center_number = number / 10 * 10 + 5;
+            if coord < 0 {
+                -center_number - 1
+            } else {
+                center_number
+            }
+        };
+
+        // the largest center is 125, so this is always within bounds
+        Self::from_coords(center(self.x_coord()), center(self.y_coord()))
+            .expect("expected sector center to be a valid room name")
+    }
+}
+
+impl RoomName {
+    /// Gets the linear distance between two rooms, the number of rooms moved
+    /// along the longer axis, matching [`game::map::get_room_linear_distance`].
+    ///
+    /// If `continuous_world_size` is set, the world is treated as wrapping
+    /// around at its edges, with the given width and height in rooms, as with
+    /// `continuous` set to `true` for the game's function; pass
+    /// [`game::map::get_world_size`] to match it exactly.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomName;
+    ///
+    /// let w5n5 = RoomName::new("W5N5").unwrap();
+    /// let e5n8 = RoomName::new("E5N8").unwrap();
+    ///
+    /// assert_eq!(w5n5.linear_distance(e5n8, None), 11);
+    /// // the direct distance across the world is longer than wrapping around
+    /// // its edge in a world 14 rooms wide
+    /// assert_eq!(w5n5.linear_distance(e5n8, Some(14)), 3);
+    /// ```
+    ///
+    /// [`game::map::get_room_linear_distance`]: crate::game::map::get_room_linear_distance
+    /// [`game::map::get_world_size`]: crate::game::map::get_world_size
+    pub fn linear_distance(&self, other: RoomName, continuous_world_size: Option<u32>) -> u32 {
+        let mut dx = self.x_coord().abs_diff(other.x_coord());
+        let mut dy = self.y_coord().abs_diff(other.y_coord());
+        if let Some(world_size) = continuous_world_size {
+            dx = dx.min(world_size.saturating_sub(dx));
+            dy = dy.min(world_size.saturating_sub(dy));
+        }
+        dx.max(dy)
+    }
+
+    /// Iterates over every room within the given linear (Chebyshev) range of
+    /// this room, including this room itself, in the same order as
+    /// [`RoomName`]'s [`Ord`] implementation.
+    ///
+    /// Rooms outside of the valid room name bounds are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomName;
+    ///
+    /// let center = RoomName::new("W0N0").unwrap();
+    /// let rooms: Vec<_> = center.rooms_in_range(1).map(|room| room.to_string()).collect();
+    ///
+    /// assert_eq!(
+    ///     rooms,
+    ///     ["W1N1", "W0N1", "E0N1", "W1N0", "W0N0", "E0N0", "W1S0", "W0S0", "E0S0"]
+    /// );
+    /// ```
+    pub fn rooms_in_range(&self, range: u32) -> impl Iterator<Item = RoomName> {
+        let range = range.min(VALID_ROOM_NAME_COORDINATES.len() as u32) as i32;
+        let (x, y) = (self.x_coord(), self.y_coord());
+        rooms_in_coord_rect((x - range, y - range), (x + range, y + range))
+    }
+
+    /// Iterates over every room within the given Manhattan range of this room,
+    /// the sum of the distances moved along each axis, including this room
+    /// itself, in the same order as [`RoomName`]'s [`Ord`] implementation.
+    ///
+    /// Rooms outside of the valid room name bounds are skipped.
+    pub fn rooms_in_manhattan_range(&self, range: u32) -> impl Iterator<Item = RoomName> {
+        let (x, y) = (self.x_coord(), self.y_coord());
+        self.rooms_in_range(range)
+            .filter(move |room| x.abs_diff(room.x_coord()) + y.abs_diff(room.y_coord()) <= range)
+    }
+
+    /// Iterates over every room in the rectangle with this room and another
+    /// room as opposite corners, in the same order as [`RoomName`]'s [`Ord`]
+    /// implementation.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomName;
+    ///
+    /// let corner = RoomName::new("E1S3").unwrap();
+    /// let other_corner = RoomName::new("E3S2").unwrap();
+    /// let rooms: Vec<_> = corner
+    ///     .rooms_in_rect(other_corner)
+    ///     .map(|room| room.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(rooms, ["E1S2", "E2S2", "E3S2", "E1S3", "E2S3", "E3S3"]);
+    /// ```
+    pub fn rooms_in_rect(&self, other: RoomName) -> impl Iterator<Item = RoomName> {
+        let (x1, y1) = (self.x_coord(), self.y_coord());
+        let (x2, y2) = (other.x_coord(), other.y_coord());
+        rooms_in_coord_rect((x1.min(x2), y1.min(y2)), (x1.max(x2), y1.max(y2)))
+    }
+
+    /// Iterates over every room within the given linear range of this room in
+    /// a spiral, starting with this room itself.
+    ///
+    /// Each ring of rooms at the same range is visited clockwise, starting
+    /// from its top left corner, before moving on to the next ring. Rooms
+    /// outside of the valid room name bounds are skipped.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::local::RoomName;
+    ///
+    /// let center = RoomName::new("E5S5").unwrap();
+    /// let rooms: Vec<_> = center
+    ///     .rooms_in_spiral(1)
+    ///     .map(|room| room.to_string())
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     rooms,
+    ///     ["E5S5", "E4S4", "E5S4", "E6S4", "E6S5", "E6S6", "E5S6", "E4S6", "E4S5"]
+    /// );
+    /// ```
+    pub fn rooms_in_spiral(&self, range: u32) -> impl Iterator<Item = RoomName> {
+        let center = *self;
+        let range = range.min(VALID_ROOM_NAME_COORDINATES.len() as u32) as i32;
+        (0..=range)
+            .flat_map(ring_offsets)
+            .filter_map(move |offset| center.checked_add(offset))
+    }
+}
+
+/// Iterates over the rooms with coordinates between two corners, inclusive,
+/// clamped to the valid room name bounds.
+fn rooms_in_coord_rect(
+    (min_x, min_y): (i32, i32),
+    (max_x, max_y): (i32, i32),
+) -> impl Iterator<Item = RoomName> {
+    let bounds_min = VALID_ROOM_NAME_COORDINATES.start;
+    let bounds_max = VALID_ROOM_NAME_COORDINATES.end - 1;
+    let (min_x, max_x) = (min_x.max(bounds_min), max_x.min(bounds_max));
+    let (min_y, max_y) = (min_y.max(bounds_min), max_y.min(bounds_max));
+
+    (min_y..=max_y).flat_map(move |y| {
+        (min_x..=max_x).map(move |x| {
+            RoomName::from_coords(x, y).expect("expected clamped coordinates to be in bounds")
+        })
+    })
+}
+
+/// Gets the position of a room coordinate within its sector, from 0 for
+/// highways to 9.
+fn sector_offset(coord: i32) -> i32 {
+    let number = if coord < 0 { -coord - 1 } else { coord };
+    number % 10
+}
+
+/// The kind of a room, based on its position within its sector.
+///
+/// See [`RoomName::kind`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RoomKind {
+    /// A normal room, which may have a controller.
+    Normal,
+    /// A highway room between sectors, which may have power banks and
+    /// deposits.
+    Highway,
+    /// A room where two highways cross.
+    Crossroad,
+    /// One of the source keeper rooms around the center of a sector.
+    SourceKeeper,
+    /// The room at the center of a sector.
+    SectorCenter,
 }
 
 impl From<RoomName> for JsValue {
@@ -651,4 +918,115 @@ mod test {
         assert_eq!(w127n5.checked_add((i32::MIN, 0)), None);
         assert_eq!(w127n5.checked_add((i32::MIN, i32::MAX)), None);
     }
+
+    #[test]
+    fn kind_and_sector() {
+        use super::RoomKind;
+
+        let kinds = [
+            ("W0N0", RoomKind::Crossroad),
+            ("E0S0", RoomKind::Crossroad),
+            ("W10S120", RoomKind::Crossroad),
+            ("W0N1", RoomKind::Highway),
+            ("E9S10", RoomKind::Highway),
+            ("E1S1", RoomKind::Normal),
+            ("W9N9", RoomKind::Normal),
+            ("W4N4", RoomKind::SourceKeeper),
+            ("E6S4", RoomKind::SourceKeeper),
+            ("W15N15", RoomKind::SectorCenter),
+            ("E125S125", RoomKind::SectorCenter),
+            ("E3S5", RoomKind::Normal),
+        ];
+        for (name, kind) in kinds {
+            let room = RoomName::new(name).unwrap();
+            assert_eq!(room.kind(), kind, "{name}");
+            assert_eq!(
+                room.is_highway(),
+                matches!(kind, RoomKind::Highway | RoomKind::Crossroad)
+            );
+            assert_eq!(room.is_crossroad(), kind == RoomKind::Crossroad);
+            assert_eq!(room.is_source_keeper_room(), kind == RoomKind::SourceKeeper);
+            assert_eq!(room.is_sector_center(), kind == RoomKind::SectorCenter);
+        }
+
+        let sectors = [
+            ("E0S0", "E5S5"),
+            ("W0N0", "W5N5"),
+            ("W9N1", "W5N5"),
+            ("E10N19", "E15N15"),
+            ("W127N127", "W125N125"),
+            ("E127S120", "E125S125"),
+        ];
+        for (name, center) in sectors {
+            let sector = RoomName::new(name).unwrap().sector();
+            assert_eq!(sector, center, "{name}");
+            assert!(sector.is_sector_center());
+        }
+    }
+
+    #[test]
+    fn linear_distance() {
+        let w0n0 = RoomName::new("W0N0").unwrap();
+        let e0s0 = RoomName::new("E0S0").unwrap();
+        let w10n3 = RoomName::new("W10N3").unwrap();
+
+        assert_eq!(w0n0.linear_distance(w0n0, None), 0);
+        assert_eq!(w0n0.linear_distance(e0s0, None), 1);
+        assert_eq!(w10n3.linear_distance(e0s0, None), 11);
+        assert_eq!(e0s0.linear_distance(w10n3, None), 11);
+
+        // a world of W10N10 to E9S9 wraps W10 around to E9
+        let e9s0 = RoomName::new("E9S0").unwrap();
+        assert_eq!(w10n3.linear_distance(e9s0, None), 20);
+        assert_eq!(w10n3.linear_distance(e9s0, Some(20)), 4);
+        assert_eq!(w10n3.linear_distance(e0s0, Some(20)), 9);
+    }
+
+    #[test]
+    fn area_iterators() {
+        let center = RoomName::new("E2S2").unwrap();
+
+        let in_range: Vec<_> = center.rooms_in_range(2).collect();
+        assert_eq!(in_range.len(), 25);
+        assert!(in_range.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(in_range
+            .iter()
+            .all(|room| center.linear_distance(*room, None) <= 2));
+
+        let manhattan: Vec<_> = center.rooms_in_manhattan_range(2).collect();
+        assert_eq!(manhattan.len(), 13);
+        assert!(!manhattan.contains(&RoomName::new("E0S0").unwrap()));
+        assert!(manhattan.contains(&RoomName::new("E0S2").unwrap()));
+
+        let spiral: Vec<_> = center.rooms_in_spiral(2).collect();
+        assert_eq!(spiral.len(), 25);
+        assert_eq!(spiral[0], center);
+        let mut sorted = spiral.clone();
+        sorted.sort();
+        assert_eq!(sorted, in_range);
+        // rings are visited in order, each ring continuing from the last
+        assert!(spiral
+            .windows(2)
+            .all(|pair| center.linear_distance(pair[0], None)
+                <= center.linear_distance(pair[1], None)));
+        assert!(spiral[9..]
+            .windows(2)
+            .all(|pair| pair[0].linear_distance(pair[1], None) == 1));
+
+        let rect: Vec<_> = center.rooms_in_rect(center + (-2, 2)).collect();
+        assert_eq!(rect.len(), 9);
+        assert_eq!(rect.first(), Some(&RoomName::new("E0S2").unwrap()));
+        assert_eq!(rect.last(), Some(&RoomName::new("E2S4").unwrap()));
+    }
+
+    #[test]
+    fn area_iterators_at_world_edge() {
+        let corner = RoomName::new("W127N127").unwrap();
+
+        assert_eq!(corner.rooms_in_range(1).count(), 4);
+        assert_eq!(corner.rooms_in_manhattan_range(1).count(), 3);
+        assert_eq!(corner.rooms_in_spiral(1).count(), 4);
+        assert_eq!(corner.rooms_in_range(u32::MAX).count(), 256 * 256);
+        assert_eq!(corner.rooms_in_spiral(u32::MAX).count(), 256 * 256);
+    }
 }
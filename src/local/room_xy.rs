@@ -237,10 +237,10 @@ impl RoomXY {
     /// assert!(one_one_neighbors.contains(&two_one));
     /// assert!(one_one_neighbors.contains(&two_zero));
     /// ```
+    ///
+    /// See [`RoomXY::neighbors_iter`] for a variant which doesn't allocate.
     pub fn neighbors(self) -> Vec<RoomXY> {
-        Direction::iter()
-            .filter_map(|dir| self.checked_add_direction(*dir))
-            .collect()
+        self.neighbors_iter().collect()
     }
 }
 
@@ -0,0 +1,509 @@
+//! Sets of tiles in a room, stored as a bitset with one bit per tile.
+use std::{
+    fmt,
+    iter::FusedIterator,
+    ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Sub, SubAssign},
+};
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::constants::{ROOM_AREA, ROOM_SIZE};
+
+use super::{linear_index_to_xy, xy_to_linear_index, RoomXY};
+
+/// Number of 64-bit words needed to store one bit per tile.
+const WORDS: usize = ROOM_AREA.div_ceil(64);
+
+/// Number of bytes in the serialized form of a set.
+const SERIALIZED_LEN: usize = ROOM_AREA.div_ceil(8);
+
+/// Bits of every tile with the given y coordinate.
+const fn row_mask(y: usize) -> [u64; WORDS] {
+    let mut mask = [0; WORDS];
+    let mut x = 0;
+    while x < ROOM_SIZE as usize {
+        let idx = x * ROOM_SIZE as usize + y;
+        mask[idx / 64] |= 1 << (idx % 64);
+        x += 1;
+    }
+    mask
+}
+
+/// Bits of every tile in the room, leaving out the unused bits of the last
+/// word.
+const fn area_mask() -> [u64; WORDS] {
+    let mut mask = [u64::MAX; WORDS];
+    mask[WORDS - 1] = (1 << (ROOM_AREA % 64)) - 1;
+    mask
+}
+
+const TOP_ROW: [u64; WORDS] = row_mask(0);
+const BOTTOM_ROW: [u64; WORDS] = row_mask(ROOM_SIZE as usize - 1);
+const AREA: [u64; WORDS] = area_mask();
+
+/// A set of coordinates within a room, stored as one bit per tile.
+///
+/// Tiles are stored in the same order as [`LocalCostMatrix`], using
+/// [`xy_to_linear_index`], and iterated over in that order.
+///
+/// # Example
+///
+/// ```
+/// use screeps::local::{RoomXY, RoomXYSet};
+///
+/// let xy = |x, y| RoomXY::checked_new(x, y).unwrap();
+///
+/// let mut walls: RoomXYSet = [xy(10, 10), xy(10, 11)].into_iter().collect();
+/// assert!(walls.contains(xy(10, 11)));
+/// assert!(walls.insert(xy(11, 10)));
+/// assert_eq!(walls.len(), 3);
+///
+/// // every tile within range 1 of a wall
+/// let near_walls = walls.dilate(1);
+/// assert_eq!(near_walls.len(), 15);
+/// assert!((&near_walls - &walls).contains(xy(12, 11)));
+/// assert!(!near_walls.contains(xy(12, 12)));
+/// ```
+///
+/// [`LocalCostMatrix`]: crate::local::LocalCostMatrix
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct RoomXYSet {
+    bits: [u64; WORDS],
+}
+
+impl Default for RoomXYSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RoomXYSet {
+    /// Creates an empty set.
+    #[inline]
+    pub const fn new() -> Self {
+        RoomXYSet { bits: [0; WORDS] }
+    }
+
+    /// Creates a set containing every tile of a room.
+    #[inline]
+    pub const fn full() -> Self {
+        RoomXYSet { bits: AREA }
+    }
+
+    /// Adds a tile to the set, returning whether it wasn't already present.
+    #[inline]
+    pub fn insert(&mut self, xy: RoomXY) -> bool {
+        let (word, bit) = Self::bit_of(xy);
+        let absent = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+        absent
+    }
+
+    /// Removes a tile from the set, returning whether it was present.
+    #[inline]
+    pub fn remove(&mut self, xy: RoomXY) -> bool {
+        let (word, bit) = Self::bit_of(xy);
+        let present = self.bits[word] & bit != 0;
+        self.bits[word] &= !bit;
+        present
+    }
+
+    /// Gets whether a tile is in the set.
+    #[inline]
+    pub fn contains(&self, xy: RoomXY) -> bool {
+        let (word, bit) = Self::bit_of(xy);
+        self.bits[word] & bit != 0
+    }
+
+    /// Gets the number of tiles in the set.
+    pub fn len(&self) -> usize {
+        self.bits
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Gets whether the set contains no tiles.
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|&word| word == 0)
+    }
+
+    /// Removes every tile from the set.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.bits = [0; WORDS];
+    }
+
+    /// Gets a set of the tiles in either this set or the other.
+    #[inline]
+    pub fn union(&self, other: &RoomXYSet) -> RoomXYSet {
+        self | other
+    }
+
+    /// Gets a set of the tiles in both this set and the other.
+    #[inline]
+    pub fn intersection(&self, other: &RoomXYSet) -> RoomXYSet {
+        self & other
+    }
+
+    /// Gets a set of the tiles in this set but not the other.
+    #[inline]
+    pub fn difference(&self, other: &RoomXYSet) -> RoomXYSet {
+        self - other
+    }
+
+    /// Gets a set of the tiles in exactly one of this set and the other.
+    #[inline]
+    pub fn symmetric_difference(&self, other: &RoomXYSet) -> RoomXYSet {
+        self ^ other
+    }
+
+    /// Gets whether every tile of this set is also in the other.
+    pub fn is_subset(&self, other: &RoomXYSet) -> bool {
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .all(|(a, b)| a & !b == 0)
+    }
+
+    /// Gets whether this set has no tiles in common with the other.
+    pub fn is_disjoint(&self, other: &RoomXYSet) -> bool {
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .all(|(a, b)| a & b == 0)
+    }
+
+    /// Iterates over the tiles in the set, in linear index order.
+    #[inline]
+    pub fn iter(&self) -> RoomXYSetIter<'_> {
+        RoomXYSetIter {
+            bits: &self.bits,
+            word_idx: 0,
+            word: self.bits[0],
+        }
+    }
+
+    /// Gets a set of every tile within the given range of a tile in this set,
+    /// including the tiles of this set.
+    ///
+    /// Range is measured the same way as [`RoomXY::get_range_to`], so each
+    /// tile grows into a square of side `range * 2 + 1`, clipped to the room.
+    pub fn dilate(&self, range: u8) -> RoomXYSet {
+        let range = range.min(ROOM_SIZE - 1);
+        let mut bits = self.bits;
+
+        // tiles are stored in columns, so neighbors along y are one bit away
+        // and neighbors along x are a whole column away
+        for _ in 0..range {
+            let plus_y = shift_up(&bits, 1);
+            let minus_y = shift_down(&bits, 1);
+            for idx in 0..WORDS {
+                bits[idx] |=
+                    ((plus_y[idx] & !TOP_ROW[idx]) | (minus_y[idx] & !BOTTOM_ROW[idx])) & AREA[idx];
+            }
+        }
+        for _ in 0..range {
+            let plus_x = shift_up(&bits, ROOM_SIZE as usize);
+            let minus_x = shift_down(&bits, ROOM_SIZE as usize);
+            for idx in 0..WORDS {
+                bits[idx] |= (plus_x[idx] | minus_x[idx]) & AREA[idx];
+            }
+        }
+
+        RoomXYSet { bits }
+    }
+
+    #[inline]
+    fn bit_of(xy: RoomXY) -> (usize, u64) {
+        let idx = xy_to_linear_index(xy);
+        (idx / 64, 1 << (idx % 64))
+    }
+}
+
+/// Shifts every bit to a higher index, dropping bits moved out of the end.
+fn shift_up(bits: &[u64; WORDS], by: usize) -> [u64; WORDS] {
+    let (words, offset) = (by / 64, by % 64);
+    let mut shifted = [0; WORDS];
+    for idx in words..WORDS {
+        shifted[idx] = bits[idx - words] << offset;
+        if offset != 0 && idx > words {
+            shifted[idx] |= bits[idx - words - 1] >> (64 - offset);
+        }
+    }
+    shifted
+}
+
+/// Shifts every bit to a lower index, dropping bits moved out of the start.
+fn shift_down(bits: &[u64; WORDS], by: usize) -> [u64; WORDS] {
+    let (words, offset) = (by / 64, by % 64);
+    let mut shifted = [0; WORDS];
+    for idx in 0..WORDS - words {
+        shifted[idx] = bits[idx + words] >> offset;
+        if offset != 0 && idx + words + 1 < WORDS {
+            shifted[idx] |= bits[idx + words + 1] << (64 - offset);
+        }
+    }
+    shifted
+}
+
+impl fmt::Debug for RoomXYSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// An iterator over the tiles of a [`RoomXYSet`], in linear index order.
+pub struct RoomXYSetIter<'a> {
+    bits: &'a [u64; WORDS],
+    word_idx: usize,
+    word: u64,
+}
+
+impl Iterator for RoomXYSetIter<'_> {
+    type Item = RoomXY;
+
+    fn next(&mut self) -> Option<RoomXY> {
+        while self.word == 0 {
+            self.word_idx += 1;
+            self.word = *self.bits.get(self.word_idx)?;
+        }
+        let bit = self.word.trailing_zeros() as usize;
+        // clear the lowest set bit
+        self.word &= self.word - 1;
+        Some(linear_index_to_xy(self.word_idx * 64 + bit))
+    }
+}
+
+impl FusedIterator for RoomXYSetIter<'_> {}
+
+impl<'a> IntoIterator for &'a RoomXYSet {
+    type Item = RoomXY;
+    type IntoIter = RoomXYSetIter<'a>;
+
+    fn into_iter(self) -> RoomXYSetIter<'a> {
+        self.iter()
+    }
+}
+
+impl Extend<RoomXY> for RoomXYSet {
+    fn extend<T: IntoIterator<Item = RoomXY>>(&mut self, iter: T) {
+        for xy in iter {
+            self.insert(xy);
+        }
+    }
+}
+
+impl FromIterator<RoomXY> for RoomXYSet {
+    fn from_iter<T: IntoIterator<Item = RoomXY>>(iter: T) -> Self {
+        let mut set = RoomXYSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+macro_rules! impl_set_op {
+    ($op:ident, $op_fn:ident, $assign:ident, $assign_fn:ident, |$a:ident, $b:ident| $expr:expr) => {
+        impl $op<&RoomXYSet> for &RoomXYSet {
+            type Output = RoomXYSet;
+
+            fn $op_fn(self, other: &RoomXYSet) -> RoomXYSet {
+                let mut set = self.clone();
+                set.$assign_fn(other);
+                set
+            }
+        }
+
+        impl $assign<&RoomXYSet> for RoomXYSet {
+            fn $assign_fn(&mut self, other: &RoomXYSet) {
+                for ($a, &$b) in self.bits.iter_mut().zip(other.bits.iter()) {
+                    *$a = $expr;
+                }
+            }
+        }
+    };
+}
+
+impl_set_op!(BitOr, bitor, BitOrAssign, bitor_assign, |a, b| *a | b);
+impl_set_op!(BitAnd, bitand, BitAndAssign, bitand_assign, |a, b| *a & b);
+impl_set_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, |a, b| *a ^ b);
+impl_set_op!(Sub, sub, SubAssign, sub_assign, |a, b| *a & !b);
+
+// serialized as the bits of each tile in linear index order, packed 8 to a
+// byte
+impl Serialize for RoomXYSet {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = [0; SERIALIZED_LEN];
+        for (idx, byte) in bytes.iter_mut().enumerate() {
+            *byte = (self.bits[idx / 8] >> ((idx % 8) * 8)) as u8;
+        }
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+impl<'de> Deserialize<'de> for RoomXYSet {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(RoomXYSetVisitor)
+    }
+}
+
+struct RoomXYSetVisitor;
+
+impl RoomXYSetVisitor {
+    fn from_bytes<E: de::Error>(bytes: impl ExactSizeIterator<Item = u8>) -> Result<RoomXYSet, E> {
+        if bytes.len() != SERIALIZED_LEN {
+            return Err(E::invalid_length(bytes.len(), &RoomXYSetVisitor));
+        }
+
+        let mut set = RoomXYSet::new();
+        for (idx, byte) in bytes.enumerate() {
+            set.bits[idx / 8] |= (byte as u64) << ((idx % 8) * 8);
+        }
+        if set.bits[WORDS - 1] & !AREA[WORDS - 1] != 0 {
+            return Err(E::custom("set bits past the end of the room area"));
+        }
+        Ok(set)
+    }
+}
+
+impl<'de> Visitor<'de> for RoomXYSetVisitor {
+    type Value = RoomXYSet;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{SERIALIZED_LEN} bytes of packed tile bits")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Self::from_bytes(bytes.iter().copied())
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Vec::with_capacity(SERIALIZED_LEN);
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        Self::from_bytes(bytes.into_iter())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    #[test]
+    fn insert_remove_and_iterate() {
+        let mut set = RoomXYSet::new();
+        assert!(set.is_empty());
+
+        let tiles = [xy(49, 49), xy(0, 0), xy(1, 13), xy(25, 0), xy(0, 49)];
+        for tile in tiles {
+            assert!(set.insert(tile));
+            assert!(!set.insert(tile));
+        }
+        assert_eq!(set.len(), 5);
+
+        let mut expected = tiles.to_vec();
+        expected.sort_by_key(|&tile| xy_to_linear_index(tile));
+        assert_eq!(set.iter().collect::<Vec<_>>(), expected);
+
+        assert!(set.remove(xy(1, 13)));
+        assert!(!set.remove(xy(1, 13)));
+        assert!(!set.contains(xy(1, 13)));
+        assert!(set.contains(xy(49, 49)));
+        assert_eq!(set.len(), 4);
+
+        assert_eq!(RoomXYSet::full().len(), ROOM_AREA);
+        assert_eq!(RoomXYSet::full().iter().count(), ROOM_AREA);
+    }
+
+    #[test]
+    fn set_algebra() {
+        let a: RoomXYSet = [xy(1, 1), xy(2, 2), xy(3, 3)].into_iter().collect();
+        let b: RoomXYSet = [xy(3, 3), xy(4, 4)].into_iter().collect();
+
+        assert_eq!(a.union(&b).len(), 4);
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), [xy(3, 3)]);
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            [xy(1, 1), xy(2, 2)]
+        );
+        assert_eq!(a.symmetric_difference(&b).len(), 3);
+
+        assert!(a.intersection(&b).is_subset(&a));
+        assert!(!a.is_subset(&b));
+        assert!(a.difference(&b).is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+
+        let mut c = a.clone();
+        c -= &b;
+        c |= &b;
+        assert_eq!(c, a.union(&b));
+    }
+
+    #[test]
+    fn dilate() {
+        let corner: RoomXYSet = [xy(0, 0)].into_iter().collect();
+        assert_eq!(corner.dilate(0), corner);
+        assert_eq!(corner.dilate(2).len(), 9);
+
+        // bits on the edge of a column must not spill over into the next one
+        let edge: RoomXYSet = [xy(10, 49)].into_iter().collect();
+        let dilated = edge.dilate(1);
+        assert_eq!(dilated.len(), 6);
+        assert!(!dilated.contains(xy(11, 0)));
+        assert!(!dilated.contains(xy(10, 0)));
+
+        // nor past the end of the room from the last tiles
+        for tile in [xy(49, 49), xy(0, 49)] {
+            let dilated = [tile].into_iter().collect::<RoomXYSet>().dilate(1);
+            assert_eq!(dilated.len(), 4);
+            assert_eq!(dilated.iter().count(), 4);
+            assert!(dilated.iter().all(|near| near.get_range_to(tile) <= 1));
+        }
+
+        let center: RoomXYSet = [xy(25, 25)].into_iter().collect();
+        let dilated = center.dilate(3);
+        assert_eq!(dilated.len(), 49);
+        assert!(dilated
+            .iter()
+            .all(|tile| tile.get_range_to(xy(25, 25)) <= 3));
+
+        assert_eq!(center.dilate(u8::MAX), RoomXYSet::full());
+    }
+
+    #[test]
+    fn serde() {
+        let set: RoomXYSet = [xy(0, 0), xy(13, 37), xy(49, 49)].into_iter().collect();
+
+        let binary = bincode::serialize(&set).unwrap();
+        assert_eq!(bincode::deserialize::<RoomXYSet>(&binary).unwrap(), set);
+
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(serde_json::from_str::<RoomXYSet>(&json).unwrap(), set);
+
+        assert!(serde_json::from_str::<RoomXYSet>("[1, 2, 3]").is_err());
+        let mut overflowing = vec![0; SERIALIZED_LEN];
+        overflowing[SERIALIZED_LEN - 1] = 0xFF;
+        let json = serde_json::to_string(&overflowing).unwrap();
+        assert!(serde_json::from_str::<RoomXYSet>(&json).is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! Room-level route finding performed entirely in Rust memory, mirroring
+//! [`game::map::find_route`].
+//!
+//! [`game::map::find_route`]: crate::game::map::find_route
+use std::{
+    cmp::Ordering,
+    collections::{hash_map::Entry, BinaryHeap, HashMap},
+};
+
+use crate::{
+    constants::{ErrorCode, ExitDirection},
+    game::map::{FindRouteOptions, RouteStep},
+};
+
+use super::RoomName;
+
+/// The lowest cost found to reach each room, and the room and exit it was
+/// entered from.
+type VisitedRooms = HashMap<RoomName, (f64, Option<(RoomName, ExitDirection)>)>;
+
+/// Finds the route from one room to another, using the same algorithm as
+/// [`game::map::find_route`] but with the exits of each room provided by the
+/// caller.
+///
+/// The `exits` callback returns the exits of a room along with the rooms
+/// they lead to, as from [`game::map::describe_exits`] or a cache of it.
+/// Entering a room costs the value returned by the route callback of the
+/// options, called with the room being entered and the room being left; a
+/// cost of [`f64::INFINITY`] blocks entry. Like the game's implementation,
+/// this is an A* search guided by the Manhattan distance in rooms, so costs
+/// below 1 may lead to routes which aren't the cheapest.
+///
+/// Returns an empty route if both rooms are the same, and
+/// [`ErrorCode::NoPath`] if no route exists.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::ExitDirection,
+///     game::map::FindRouteOptions,
+///     local::{local_find_route, RoomName},
+/// };
+///
+/// // every room is connected to its neighbors on all four sides
+/// let exits = |room: RoomName| {
+///     [
+///         (ExitDirection::Top, (0, -1)),
+///         (ExitDirection::Right, (1, 0)),
+///         (ExitDirection::Bottom, (0, 1)),
+///         (ExitDirection::Left, (-1, 0)),
+///     ]
+///     .into_iter()
+///     .filter_map(move |(exit, offset)| Some((exit, room.checked_add(offset)?)))
+/// };
+///
+/// let from: RoomName = "W1N1".parse().unwrap();
+/// let to: RoomName = "W3N1".parse().unwrap();
+///
+/// let route = local_find_route(from, to, exits, Some(FindRouteOptions::default())).unwrap();
+/// assert_eq!(route.len(), 2);
+/// assert!(route.iter().all(|step| step.exit == ExitDirection::Left));
+///
+/// // avoid the room in between
+/// let blocked: RoomName = "W2N1".parse().unwrap();
+/// let options = FindRouteOptions::new()
+///     .room_callback(|to_room, _| if to_room == blocked { f64::INFINITY } else { 1.0 });
+/// let route = local_find_route(from, to, exits, Some(options)).unwrap();
+/// assert_eq!(route.len(), 4);
+/// ```
+///
+/// [`game::map::find_route`]: crate::game::map::find_route
+/// [`game::map::describe_exits`]: crate::game::map::describe_exits
+pub fn local_find_route<E, I, F>(
+    from: RoomName,
+    to: RoomName,
+    exits: E,
+    options: Option<FindRouteOptions<F>>,
+) -> Result<Vec<RouteStep>, ErrorCode>
+where
+    E: FnMut(RoomName) -> I,
+    I: IntoIterator<Item = (ExitDirection, RoomName)>,
+    F: FnMut(RoomName, RoomName) -> f64,
+{
+    match options {
+        Some(options) => find_route_with_options(from, to, exits, options),
+        None => find_route_with_options(from, to, exits, FindRouteOptions::default()),
+    }
+}
+
+fn find_route_with_options<E, I, F>(
+    from: RoomName,
+    to: RoomName,
+    mut exits: E,
+    mut options: FindRouteOptions<F>,
+) -> Result<Vec<RouteStep>, ErrorCode>
+where
+    E: FnMut(RoomName) -> I,
+    I: IntoIterator<Item = (ExitDirection, RoomName)>,
+    F: FnMut(RoomName, RoomName) -> f64,
+{
+    if from == to {
+        return Ok(Vec::new());
+    }
+
+    let heuristic = |room: RoomName| {
+        ((room.x_coord() - to.x_coord()).abs() + (room.y_coord() - to.y_coord()).abs()) as f64
+    };
+
+    let mut visited: VisitedRooms = HashMap::new();
+    let mut open = BinaryHeap::new();
+
+    visited.insert(from, (0.0, None));
+    open.push(OpenRoom {
+        f: heuristic(from),
+        g: 0.0,
+        room: from,
+    });
+
+    while let Some(OpenRoom { g, room, .. }) = open.pop() {
+        if room == to {
+            return Ok(build_route(&visited, to));
+        }
+        if visited.get(&room).is_some_and(|&(best, _)| best < g) {
+            continue;
+        }
+
+        for (exit, next) in exits(room) {
+            let cost = options.room_cost(next, room);
+            if !cost.is_finite() {
+                continue;
+            }
+
+            let next_g = g + cost;
+            match visited.entry(next) {
+                Entry::Occupied(entry) if entry.get().0 <= next_g => continue,
+                entry => {
+                    entry.insert_entry((next_g, Some((room, exit))));
+                }
+            }
+            open.push(OpenRoom {
+                f: next_g + heuristic(next),
+                g: next_g,
+                room: next,
+            });
+        }
+    }
+
+    Err(ErrorCode::NoPath)
+}
+
+/// Follows the steps entering each room back from the destination.
+fn build_route(visited: &VisitedRooms, to: RoomName) -> Vec<RouteStep> {
+    let mut route = Vec::new();
+    let mut room = to;
+    while let Some(&(_, Some((previous, exit)))) = visited.get(&room) {
+        route.push(RouteStep { exit, room });
+        room = previous;
+    }
+    route.reverse();
+    route
+}
+
+struct OpenRoom {
+    f: f64,
+    g: f64,
+    room: RoomName,
+}
+
+impl Ord for OpenRoom {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // reversed, so the heap pops the lowest cost first
+        other
+            .f
+            .total_cmp(&self.f)
+            .then_with(|| other.room.cmp(&self.room))
+    }
+}
+
+impl PartialOrd for OpenRoom {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for OpenRoom {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenRoom {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn room(name: &str) -> RoomName {
+        name.parse().unwrap()
+    }
+
+    /// Exits of an open grid of rooms, except for walls between rooms.
+    fn grid_exits(
+        walls: &[(RoomName, RoomName)],
+    ) -> impl FnMut(RoomName) -> Vec<(ExitDirection, RoomName)> + '_ {
+        move |from| {
+            [
+                (ExitDirection::Top, (0, -1)),
+                (ExitDirection::Right, (1, 0)),
+                (ExitDirection::Bottom, (0, 1)),
+                (ExitDirection::Left, (-1, 0)),
+            ]
+            .into_iter()
+            .filter_map(|(exit, offset)| Some((exit, from.checked_add(offset)?)))
+            .filter(|&(_, to)| !walls.contains(&(from, to)) && !walls.contains(&(to, from)))
+            .collect()
+        }
+    }
+
+    fn assert_connected(from: RoomName, route: &[RouteStep]) {
+        let mut current = from;
+        for step in route {
+            let offset = match step.exit {
+                ExitDirection::Top => (0, -1),
+                ExitDirection::Right => (1, 0),
+                ExitDirection::Bottom => (0, 1),
+                ExitDirection::Left => (-1, 0),
+            };
+            assert_eq!(current.checked_add(offset), Some(step.room));
+            current = step.room;
+        }
+    }
+
+    #[test]
+    fn straight_and_same_room() {
+        let mut exits = grid_exits(&[]);
+        let options = None::<FindRouteOptions<fn(RoomName, RoomName) -> f64>>;
+
+        let route = local_find_route(room("E1S1"), room("E1S5"), &mut exits, options).unwrap();
+        assert_eq!(
+            route.iter().map(|step| step.room).collect::<Vec<_>>(),
+            [room("E1S2"), room("E1S3"), room("E1S4"), room("E1S5")]
+        );
+        assert!(route.iter().all(|step| step.exit == ExitDirection::Bottom));
+
+        let options = None::<FindRouteOptions<fn(RoomName, RoomName) -> f64>>;
+        let route = local_find_route(room("E1S1"), room("E1S1"), &mut exits, options);
+        assert_eq!(route, Ok(vec![]));
+    }
+
+    #[test]
+    fn costs_and_walls() {
+        // a wall between W0 and E0 for most of the map, with a gap at N0/S0
+        let walls: Vec<(RoomName, RoomName)> = (-20..20)
+            .filter(|&y| y != 0)
+            .map(|y| {
+                let west = RoomName::from_coords(-1, y).unwrap();
+                let east = RoomName::from_coords(0, y).unwrap();
+                (west, east)
+            })
+            .collect();
+        let mut exits = grid_exits(&walls);
+
+        let from = room("W0N5");
+        let to = room("E0N5");
+        let options = None::<FindRouteOptions<fn(RoomName, RoomName) -> f64>>;
+        let route = local_find_route(from, to, &mut exits, options).unwrap();
+        assert_connected(from, &route);
+        assert!(route.iter().any(|step| step.room == room("E0S0")));
+        assert_eq!(route.len(), 13);
+
+        // make the gap more expensive, but still cheaper than going around the wall
+        let options =
+            FindRouteOptions::new().room_callback(
+                |to_room: RoomName, _| {
+                    if to_room == room("E0S0") {
+                        10.0
+                    } else {
+                        1.0
+                    }
+                },
+            );
+        let route = local_find_route(from, to, &mut exits, Some(options)).unwrap();
+        assert_eq!(route.len(), 13);
+
+        // blocking it forces the route around the end of the wall
+        let options = FindRouteOptions::new().room_callback(|to_room: RoomName, _| {
+            if to_room == room("E0S0") {
+                f64::INFINITY
+            } else {
+                1.0
+            }
+        });
+        let route = local_find_route(from, to, &mut exits, Some(options)).unwrap();
+        assert_connected(from, &route);
+        assert_eq!(route.len(), 31);
+
+        // and blocking the rooms past the ends of the wall leaves no route
+        let options = FindRouteOptions::new().room_callback(|to_room: RoomName, _| {
+            if to_room == room("E0S0") || !(-20..20).contains(&to_room.y_coord()) {
+                f64::INFINITY
+            } else {
+                1.0
+            }
+        });
+        let route = local_find_route(from, to, &mut exits, Some(options));
+        assert_eq!(route, Err(ErrorCode::NoPath));
+    }
+
+    #[test]
+    fn avoids_expensive_rooms() {
+        let mut exits = grid_exits(&[]);
+        let from = room("W5N5");
+        let to = room("W5N1");
+
+        let options =
+            FindRouteOptions::new().room_callback(
+                |to_room: RoomName, _| {
+                    if to_room == room("W5N3") {
+                        10.0
+                    } else {
+                        1.0
+                    }
+                },
+            );
+        let route = local_find_route(from, to, &mut exits, Some(options)).unwrap();
+        assert_connected(from, &route);
+        assert!(!route.iter().any(|step| step.room == room("W5N3")));
+        assert_eq!(route.len(), 6);
+    }
+}
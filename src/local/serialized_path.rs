@@ -0,0 +1,212 @@
+//! Encoding and decoding of paths in the compact string format used by
+//! [`Room::serialize_path`] and [`Room::deserialize_path`].
+//!
+//! [`Room::serialize_path`]: crate::objects::Room::serialize_path
+//! [`Room::deserialize_path`]: crate::objects::Room::deserialize_path
+use std::{error::Error, fmt};
+
+use num_traits::FromPrimitive;
+
+use crate::{constants::Direction, objects::Step};
+
+/// Serializes a path into the game's compact string format.
+///
+/// The string starts with the x and y coordinates of the first step as two
+/// digits each, followed by the direction of each step as a single digit.
+/// This produces exactly the same string as the game's
+/// [`Room.serializePath`], so paths can be shared between Rust and JS code.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{constants::Direction, local::serialize_path, objects::Step};
+///
+/// let path = [
+///     Step {
+///         x: 5,
+///         y: 11,
+///         dx: 0,
+///         dy: 1,
+///         direction: Direction::Bottom,
+///     },
+///     Step {
+///         x: 6,
+///         y: 12,
+///         dx: 1,
+///         dy: 1,
+///         direction: Direction::BottomRight,
+///     },
+/// ];
+///
+/// assert_eq!(serialize_path(&path), "051154");
+/// ```
+///
+/// [`Room.serializePath`]: https://docs.screeps.com/api/#Room.serializePath
+pub fn serialize_path(path: &[Step]) -> String {
+    let Some(first) = path.first() else {
+        return String::new();
+    };
+
+    let mut serialized = format!("{:02}{:02}", first.x, first.y);
+    serialized.extend(
+        path.iter()
+            .map(|step| char::from(b'0' + step.direction as u8)),
+    );
+    serialized
+}
+
+/// Deserializes a path from the game's compact string format, as produced
+/// by [`serialize_path`] or the game's [`Room.serializePath`].
+///
+/// # Example
+///
+/// ```
+/// use screeps::{constants::Direction, local::deserialize_path};
+///
+/// let path = deserialize_path("051154").unwrap();
+/// assert_eq!(path.len(), 2);
+/// assert_eq!((path[0].x, path[0].y), (5, 11));
+/// assert_eq!((path[1].x, path[1].y), (6, 12));
+/// assert_eq!(path[1].direction, Direction::BottomRight);
+/// ```
+///
+/// [`Room.serializePath`]: https://docs.screeps.com/api/#Room.serializePath
+pub fn deserialize_path(path: &str) -> Result<Vec<Step>, PathDeserializeError> {
+    if path.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let coordinate = |range| {
+        path.get(range)
+            .filter(|digits: &&str| digits.bytes().all(|b| b.is_ascii_digit()))
+            .and_then(|digits| digits.parse::<i32>().ok())
+            .ok_or(PathDeserializeError::InvalidStart)
+    };
+    let mut x = coordinate(0..2)?;
+    let mut y = coordinate(2..4)?;
+
+    let mut steps = Vec::with_capacity(path.len() - 4);
+    for (idx, character) in path[4..].char_indices() {
+        let direction = character.to_digit(10).and_then(Direction::from_u32).ok_or(
+            PathDeserializeError::InvalidDirection {
+                index: idx + 4,
+                character,
+            },
+        )?;
+        let (dx, dy): (i32, i32) = direction.into();
+
+        // the coordinates given are those of the first step, so only later
+        // steps move
+        if idx > 0 {
+            x += dx;
+            y += dy;
+        }
+        if x < 0 || y < 0 {
+            return Err(PathDeserializeError::NegativeCoordinate { index: idx + 4 });
+        }
+
+        steps.push(Step {
+            x: x as u32,
+            y: y as u32,
+            dx,
+            dy,
+            direction,
+        });
+    }
+
+    Ok(steps)
+}
+
+/// An error representing when a string can't be deserialized into a path.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PathDeserializeError {
+    /// The string doesn't start with two 2-digit coordinates.
+    InvalidStart,
+    /// A character of the string isn't a direction digit from 1 to 8.
+    InvalidDirection { index: usize, character: char },
+    /// The step at the given index of the string moves to a negative
+    /// coordinate.
+    NegativeCoordinate { index: usize },
+}
+
+impl Error for PathDeserializeError {}
+
+impl fmt::Display for PathDeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathDeserializeError::InvalidStart => write!(
+                f,
+                "expected serialized path to start with 4 coordinate digits"
+            ),
+            PathDeserializeError::InvalidDirection { index, character } => write!(
+                f,
+                "expected direction digit 1-8 in serialized path, found `{character}` at index \
+                 {index}"
+            ),
+            PathDeserializeError::NegativeCoordinate { index } => write!(
+                f,
+                "serialized path moves to negative coordinates at index {index}"
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        // a path stepping in every direction
+        let serialized = "2418811122334455667788";
+        let path = deserialize_path(serialized).unwrap();
+
+        assert_eq!(path.len(), 18);
+        assert_eq!(
+            (path[0].x, path[0].y, path[0].direction),
+            (24, 18, Direction::TopLeft)
+        );
+        assert_eq!((path[1].x, path[1].y), (24, 17));
+        assert_eq!((path[2].x, path[2].y, path[2].dy), (24, 16, -1));
+        assert_eq!((path[17].x, path[17].y), (24, 17));
+
+        assert_eq!(serialize_path(&path), serialized);
+    }
+
+    #[test]
+    fn small_coordinates_are_padded() {
+        let path = deserialize_path("010253").unwrap();
+        assert_eq!((path[1].x, path[1].y), (2, 2));
+        assert_eq!(serialize_path(&path), "010253");
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(serialize_path(&[]), "");
+        assert!(deserialize_path("").unwrap().is_empty());
+        assert!(deserialize_path("1010").unwrap().is_empty());
+    }
+
+    #[test]
+    fn invalid() {
+        assert_eq!(
+            deserialize_path("1x10").unwrap_err(),
+            PathDeserializeError::InvalidStart
+        );
+        assert_eq!(
+            deserialize_path("10").unwrap_err(),
+            PathDeserializeError::InvalidStart
+        );
+        assert_eq!(
+            deserialize_path("101029").unwrap_err(),
+            PathDeserializeError::InvalidDirection {
+                index: 5,
+                character: '9'
+            }
+        );
+        assert_eq!(
+            deserialize_path("000077").unwrap_err(),
+            PathDeserializeError::NegativeCoordinate { index: 5 }
+        );
+    }
+}
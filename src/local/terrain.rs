@@ -1,6 +1,10 @@
-use std::mem::MaybeUninit;
+use std::{
+    hash::{Hash, Hasher},
+    mem::MaybeUninit,
+};
 
 use js_sys::Uint8Array;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     constants::{Terrain, ROOM_AREA},
@@ -9,29 +13,37 @@ use crate::{
 
 use super::{xy_to_terrain_index, RoomXY};
 
-#[derive(Debug, Clone)]
-pub struct LocalRoomTerrain {
-    bits: Box<[u8; ROOM_AREA]>,
-}
+mod exits;
+mod packed;
+mod text;
+
+pub use self::{exits::*, packed::*, text::*};
 
 /// A matrix representing the terrain of a room, stored in Rust memory.
 ///
 /// Use [`RoomTerrain`] if data stored in JavaScript memory is preferred.
+///
+/// Serialized in the packed form of [`PackedRoomTerrain`], at 2 bits per
+/// tile. Terrains are compared by the [`Terrain`] of each tile, so they're
+/// still equal after a round trip through the packed form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "PackedRoomTerrain", into = "PackedRoomTerrain")]
+pub struct LocalRoomTerrain {
+    bits: Box<[u8; ROOM_AREA]>,
+}
+
 impl LocalRoomTerrain {
     /// Gets the terrain at the specified position in this room.
     pub fn get_xy(&self, xy: RoomXY) -> Terrain {
         // SAFETY: RoomXY is always a valid coordinate.
         let byte = unsafe { self.bits.get_unchecked(xy_to_terrain_index(xy)) };
-        // not using Terrain::from_u8() because `0b11` value, wall+swamp, happens
-        // in commonly used server environments (notably the private server default
-        // map), and is special-cased in the engine code; we special-case it here
-        match byte & 0b11 {
-            0b00 => Terrain::Plain,
-            0b01 | 0b11 => Terrain::Wall,
-            0b10 => Terrain::Swamp,
-            // Should be optimized out
-            _ => unreachable!("all combinations of 2 bits are covered"),
-        }
+        terrain_from_bits(*byte)
+    }
+
+    /// Gets the bytes that correspond to the room's terrain data, in the
+    /// row-major order described in [`LocalRoomTerrain::new_from_bits`].
+    pub fn get_bits(&self) -> &[u8; ROOM_AREA] {
+        &self.bits
     }
 
     /// Creates a `LocalRoomTerrain` from the bytes that correspond to the
@@ -49,6 +61,41 @@ impl LocalRoomTerrain {
     }
 }
 
+/// Converts the lowest 2 bits of a byte of terrain data to a [`Terrain`].
+fn terrain_from_bits(byte: u8) -> Terrain {
+    // not using Terrain::from_u8() because `0b11` value, wall+swamp, happens
+    // in commonly used server environments (notably the private server default
+    // map), and is special-cased in the engine code; we special-case it here
+    match byte & 0b11 {
+        0b00 => Terrain::Plain,
+        0b01 | 0b11 => Terrain::Wall,
+        0b10 => Terrain::Swamp,
+        // Should be optimized out
+        _ => unreachable!("all combinations of 2 bits are covered"),
+    }
+}
+
+impl PartialEq for LocalRoomTerrain {
+    fn eq(&self, other: &Self) -> bool {
+        // compare terrain rather than bytes, as wall bytes can be `0b01` or
+        // `0b11` and higher bits are ignored
+        self.bits
+            .iter()
+            .zip(other.bits.iter())
+            .all(|(&a, &b)| terrain_from_bits(a) == terrain_from_bits(b))
+    }
+}
+
+impl Eq for LocalRoomTerrain {}
+
+impl Hash for LocalRoomTerrain {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for &byte in self.bits.iter() {
+            terrain_from_bits(byte).hash(state);
+        }
+    }
+}
+
 impl From<RoomTerrain> for LocalRoomTerrain {
     fn from(terrain: RoomTerrain) -> LocalRoomTerrain {
         // create an uninitialized array of the correct size
@@ -0,0 +1,356 @@
+//! Analysis of the exits of a room from its terrain, without needing
+//! visibility of the room.
+use std::collections::VecDeque;
+
+use crate::{
+    constants::{Direction, ExitDirection, Terrain, ROOM_AREA, ROOM_SIZE},
+    local::{pathfinder::can_step_in_room, xy_to_linear_index, RoomXY},
+};
+
+use super::LocalRoomTerrain;
+
+/// A contiguous run of exit tiles along one edge of a room.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ExitSegment {
+    direction: ExitDirection,
+    start: RoomXY,
+    end: RoomXY,
+}
+
+impl ExitSegment {
+    /// The edge of the room this segment is on.
+    #[inline]
+    pub fn direction(&self) -> ExitDirection {
+        self.direction
+    }
+
+    /// The first tile of the segment, the top-most or left-most one.
+    #[inline]
+    pub fn start(&self) -> RoomXY {
+        self.start
+    }
+
+    /// The last tile of the segment, the bottom-most or right-most one.
+    #[inline]
+    pub fn end(&self) -> RoomXY {
+        self.end
+    }
+
+    /// The number of tiles in the segment.
+    #[inline]
+    pub fn width(&self) -> u8 {
+        self.start.get_range_to(self.end) + 1
+    }
+
+    /// Iterates over the tiles of the segment, from start to end.
+    pub fn tiles(&self) -> impl Iterator<Item = RoomXY> {
+        let direction = self.direction;
+        let start = position_along_edge(self.start, direction);
+        let end = position_along_edge(self.end, direction);
+        (start..=end).map(move |offset| edge_tile(direction, offset))
+    }
+
+    /// Whether a tile is part of this segment.
+    pub fn contains(&self, xy: RoomXY) -> bool {
+        self.start <= xy && xy <= self.end && on_edge(xy, self.direction)
+    }
+}
+
+/// The exits of a room, and the walking distance between each pair of exits
+/// within the room.
+///
+/// Created by [`LocalRoomTerrain::exits`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoomExits {
+    segments: Vec<ExitSegment>,
+    /// Distance between each pair of segments, indexed by `from * len + to`.
+    distances: Vec<Option<u32>>,
+}
+
+impl RoomExits {
+    /// All exit segments of the room, ordered by direction and then position
+    /// along the edge.
+    #[inline]
+    pub fn segments(&self) -> &[ExitSegment] {
+        &self.segments
+    }
+
+    /// The index of the segment containing a tile, if any.
+    pub fn segment_index(&self, xy: RoomXY) -> Option<usize> {
+        self.segments
+            .iter()
+            .position(|segment| segment.contains(xy))
+    }
+
+    /// The fewest moves needed inside the room to get from the segment at
+    /// index `from` to the segment at index `to`, or `None` if they aren't
+    /// connected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds of [`RoomExits::segments`].
+    pub fn distance(&self, from: usize, to: usize) -> Option<u32> {
+        let len = self.segments.len();
+        assert!(from < len && to < len, "exit segment index out of bounds");
+        self.distances[from * len + to]
+    }
+
+    /// Whether the segments at indices `from` and `to` are connected inside
+    /// the room.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either index is out of bounds of [`RoomExits::segments`].
+    #[inline]
+    pub fn connected(&self, from: usize, to: usize) -> bool {
+        self.distance(from, to).is_some()
+    }
+
+    /// The fewest moves needed inside the room to get from any exit in one
+    /// direction to any exit in another, or `None` if no such exits are
+    /// connected.
+    pub fn direction_distance(&self, from: ExitDirection, to: ExitDirection) -> Option<u32> {
+        let indices = |direction| {
+            self.segments
+                .iter()
+                .enumerate()
+                .filter(move |(_, segment)| segment.direction == direction)
+                .map(|(idx, _)| idx)
+        };
+
+        indices(from)
+            .flat_map(|from| indices(to).filter_map(move |to| self.distance(from, to)))
+            .min()
+    }
+}
+
+impl LocalRoomTerrain {
+    /// Iterates over the exit tiles along one edge of the room, the tiles of
+    /// that edge which aren't walls.
+    pub fn exit_tiles(&self, direction: ExitDirection) -> impl Iterator<Item = RoomXY> + '_ {
+        (0..ROOM_SIZE)
+            .map(move |offset| edge_tile(direction, offset))
+            .filter(|&xy| self.get_xy(xy) != Terrain::Wall)
+    }
+
+    /// Groups the exit tiles along one edge of the room into contiguous
+    /// segments, ordered by position along the edge.
+    pub fn exit_segments(&self, direction: ExitDirection) -> Vec<ExitSegment> {
+        let mut segments: Vec<ExitSegment> = Vec::new();
+
+        for xy in self.exit_tiles(direction) {
+            match segments.last_mut() {
+                Some(segment) if segment.end.get_range_to(xy) == 1 => segment.end = xy,
+                _ => segments.push(ExitSegment {
+                    direction,
+                    start: xy,
+                    end: xy,
+                }),
+            }
+        }
+
+        segments
+    }
+
+    /// Finds all exits of the room, and the walking distance between each
+    /// pair of them within the room.
+    ///
+    /// Distances count moves, regardless of terrain type. Paths between
+    /// exits can't pass over other exit tiles, as stepping onto one would
+    /// leave the room.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::{
+    ///     constants::{ExitDirection, ROOM_AREA},
+    ///     local::LocalRoomTerrain,
+    /// };
+    ///
+    /// // an open room, with walls only in the corners
+    /// let mut bits = Box::new([0; ROOM_AREA]);
+    /// for corner in [0, 49, 49 * 50, ROOM_AREA - 1] {
+    ///     bits[corner] = 1;
+    /// }
+    /// let terrain = LocalRoomTerrain::new_from_bits(bits);
+    ///
+    /// let exits = terrain.exits();
+    /// assert_eq!(exits.segments().len(), 4);
+    /// assert_eq!(exits.segments()[0].width(), 48);
+    /// assert_eq!(
+    ///     exits.direction_distance(ExitDirection::Top, ExitDirection::Bottom),
+    ///     Some(49)
+    /// );
+    /// ```
+    pub fn exits(&self) -> RoomExits {
+        let segments: Vec<ExitSegment> = enum_iterator::all::<ExitDirection>()
+            .flat_map(|direction| self.exit_segments(direction))
+            .collect();
+
+        let len = segments.len();
+        let mut distances = vec![None; len * len];
+        for (from_idx, from) in segments.iter().enumerate() {
+            let steps = self.steps_from_segment(from);
+            for (to_idx, to) in segments.iter().enumerate() {
+                distances[from_idx * len + to_idx] = to
+                    .tiles()
+                    .filter_map(|xy| steps[xy_to_linear_index(xy)])
+                    .min();
+            }
+        }
+
+        RoomExits {
+            segments,
+            distances,
+        }
+    }
+
+    /// Breadth-first search for the number of moves from the tiles of an exit
+    /// segment to every other tile, without moving on from other edge tiles
+    /// or stepping from one edge tile to another.
+    fn steps_from_segment(&self, segment: &ExitSegment) -> Box<[Option<u32>; ROOM_AREA]> {
+        let mut steps = Box::new([None; ROOM_AREA]);
+        let mut queue = VecDeque::new();
+
+        for xy in segment.tiles() {
+            steps[xy_to_linear_index(xy)] = Some(0);
+            queue.push_back((xy, 0));
+        }
+
+        while let Some((xy, distance)) = queue.pop_front() {
+            if distance > 0 && xy.is_room_edge() {
+                continue;
+            }
+
+            for &dir in Direction::iter() {
+                let Some(next) = xy.checked_add_direction(dir) else {
+                    continue;
+                };
+                if !can_step_in_room(xy, next) {
+                    continue;
+                }
+                let entry = &mut steps[xy_to_linear_index(next)];
+                if entry.is_none() && self.get_xy(next) != Terrain::Wall {
+                    *entry = Some(distance + 1);
+                    queue.push_back((next, distance + 1));
+                }
+            }
+        }
+
+        steps
+    }
+}
+
+/// The tile at an offset along an edge of the room.
+fn edge_tile(direction: ExitDirection, offset: u8) -> RoomXY {
+    let (x, y) = match direction {
+        ExitDirection::Top => (offset, 0),
+        ExitDirection::Right => (ROOM_SIZE - 1, offset),
+        ExitDirection::Bottom => (offset, ROOM_SIZE - 1),
+        ExitDirection::Left => (0, offset),
+    };
+    // SAFETY: callers only pass offsets in the range `0..ROOM_SIZE`
+    unsafe { RoomXY::unchecked_new(x, y) }
+}
+
+/// The offset of an edge tile along its edge.
+fn position_along_edge(xy: RoomXY, direction: ExitDirection) -> u8 {
+    match direction {
+        ExitDirection::Top | ExitDirection::Bottom => xy.x.u8(),
+        ExitDirection::Left | ExitDirection::Right => xy.y.u8(),
+    }
+}
+
+fn on_edge(xy: RoomXY, direction: ExitDirection) -> bool {
+    match direction {
+        ExitDirection::Top => xy.y.u8() == 0,
+        ExitDirection::Right => xy.x.u8() == ROOM_SIZE - 1,
+        ExitDirection::Bottom => xy.y.u8() == ROOM_SIZE - 1,
+        ExitDirection::Left => xy.x.u8() == 0,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::xy_to_terrain_index;
+
+    fn xy(x: u8, y: u8) -> RoomXY {
+        RoomXY::checked_new(x, y).unwrap()
+    }
+
+    fn terrain_with_walls(walls: impl IntoIterator<Item = RoomXY>) -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for wall in walls {
+            bits[xy_to_terrain_index(wall)] = 1;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn segments() {
+        // the top edge has exits at x = 5..=9 and x = 20..=22, and there are no
+        // other exits
+        let exit_xs = [5, 6, 7, 8, 9, 20, 21, 22];
+        let walls = (0..50)
+            .flat_map(|i| [xy(i, 0), xy(0, i), xy(49, i), xy(i, 49)])
+            .filter(|wall| wall.y.u8() != 0 || !exit_xs.contains(&wall.x.u8()));
+        let terrain = terrain_with_walls(walls);
+
+        assert_eq!(terrain.exit_tiles(ExitDirection::Top).count(), 8);
+        assert_eq!(terrain.exit_tiles(ExitDirection::Left).count(), 0);
+
+        let segments = terrain.exit_segments(ExitDirection::Top);
+        assert_eq!(segments.len(), 2);
+        assert_eq!(
+            (segments[0].start(), segments[0].end()),
+            (xy(5, 0), xy(9, 0))
+        );
+        assert_eq!(segments[0].width(), 5);
+        assert_eq!(segments[1].width(), 3);
+        assert!(segments[1].contains(xy(21, 0)));
+        assert!(!segments[1].contains(xy(9, 0)));
+        assert_eq!(
+            segments[1].tiles().collect::<Vec<_>>(),
+            vec![xy(20, 0), xy(21, 0), xy(22, 0)]
+        );
+
+        let exits = terrain.exits();
+        assert_eq!(exits.segments(), &segments[..]);
+        assert_eq!(exits.segment_index(xy(22, 0)), Some(1));
+        // from x = 9 to x = 20, through the row below the edge
+        assert_eq!(exits.distance(0, 1), Some(11));
+        assert_eq!(exits.distance(1, 0), Some(11));
+        assert_eq!(exits.distance(0, 0), Some(0));
+    }
+
+    #[test]
+    fn divided_room() {
+        // walls in the corners, and a wall from top to bottom at x = 25
+        let corners = [xy(0, 0), xy(49, 0), xy(0, 49), xy(49, 49)];
+        let divider = (0..50).map(|y| xy(25, y));
+        let terrain = terrain_with_walls(corners.into_iter().chain(divider));
+
+        let exits = terrain.exits();
+        let top = exits.segments()[0];
+        assert_eq!((top.direction(), top.width()), (ExitDirection::Top, 24));
+        assert_eq!(exits.segments().len(), 6);
+
+        let left = exits.segment_index(xy(0, 25)).unwrap();
+        let right = exits.segment_index(xy(49, 25)).unwrap();
+        let top_left = exits.segment_index(xy(10, 0)).unwrap();
+        assert!(!exits.connected(left, right));
+        assert!(exits.connected(left, top_left));
+        // off the left exit and back onto the top exit, as a creep can't step
+        // between exit tiles
+        assert_eq!(exits.distance(left, top_left), Some(2));
+        assert_eq!(
+            exits.direction_distance(ExitDirection::Left, ExitDirection::Right),
+            None
+        );
+        assert_eq!(
+            exits.direction_distance(ExitDirection::Top, ExitDirection::Bottom),
+            Some(49)
+        );
+    }
+}
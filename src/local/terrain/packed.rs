@@ -0,0 +1,301 @@
+//! Compact storage of room terrain at 2 bits per tile.
+use std::fmt;
+
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::{
+    constants::{Terrain, ROOM_AREA},
+    local::{xy_to_terrain_index, RoomXY},
+};
+
+use super::{terrain_from_bits, LocalRoomTerrain};
+
+/// The number of bytes in a [`PackedRoomTerrain`], with 4 tiles per byte.
+pub const PACKED_TERRAIN_LEN: usize = ROOM_AREA / 4;
+
+/// The terrain of a room, packed at 2 bits per tile.
+///
+/// This holds the same data as [`LocalRoomTerrain`] in a quarter of the
+/// space, for storing the terrain of many rooms at once. Tiles are in the
+/// same row-major order as [`LocalRoomTerrain::new_from_bits`], with each
+/// byte holding 4 tiles, starting from its lowest bits.
+///
+/// # Example
+///
+/// ```
+/// use screeps::{
+///     constants::{Terrain, ROOM_AREA},
+///     local::{LocalRoomTerrain, PackedRoomTerrain, RoomXY},
+/// };
+///
+/// let mut bits = Box::new([0; ROOM_AREA]);
+/// bits[5] = 1;
+/// let terrain = LocalRoomTerrain::new_from_bits(bits);
+///
+/// let packed = PackedRoomTerrain::from(&terrain);
+/// assert_eq!(packed.as_bytes().len(), 625);
+/// assert_eq!(packed.get_xy(RoomXY::checked_new(5, 0).unwrap()), Terrain::Wall);
+/// assert_eq!(LocalRoomTerrain::from(&packed), terrain);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct PackedRoomTerrain {
+    bytes: Box<[u8; PACKED_TERRAIN_LEN]>,
+}
+
+impl PackedRoomTerrain {
+    /// Creates a `PackedRoomTerrain` from its packed bytes, as returned by
+    /// [`PackedRoomTerrain::as_bytes`].
+    ///
+    /// Tiles with the `0b11` wall+swamp value are stored as plain walls, so
+    /// that terrains with the same tiles have the same bytes.
+    pub fn new_from_bytes(mut bytes: Box<[u8; PACKED_TERRAIN_LEN]>) -> Self {
+        for byte in bytes.iter_mut() {
+            // clear the high bit of each tile which has both bits set
+            let both_set = *byte & (*byte >> 1) & 0b0101_0101;
+            *byte &= !(both_set << 1);
+        }
+        PackedRoomTerrain { bytes }
+    }
+
+    /// Gets the packed bytes of the terrain.
+    pub fn as_bytes(&self) -> &[u8; PACKED_TERRAIN_LEN] {
+        &self.bytes
+    }
+
+    /// Gets the terrain at the specified position in this room.
+    pub fn get_xy(&self, xy: RoomXY) -> Terrain {
+        terrain_from_bits(self.get_bits(xy_to_terrain_index(xy)))
+    }
+
+    #[inline]
+    fn get_bits(&self, idx: usize) -> u8 {
+        self.bytes[idx / 4] >> ((idx % 4) * 2)
+    }
+}
+
+impl fmt::Debug for PackedRoomTerrain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PackedRoomTerrain")
+            .field("bytes", &&self.bytes[..])
+            .finish()
+    }
+}
+
+impl From<&LocalRoomTerrain> for PackedRoomTerrain {
+    /// Packs the [`Terrain`] of each tile, so walls are always stored as
+    /// `0b01`, even where the unpacked terrain uses the `0b11` wall+swamp
+    /// value.
+    fn from(terrain: &LocalRoomTerrain) -> Self {
+        let mut bytes = Box::new([0; PACKED_TERRAIN_LEN]);
+        for (idx, &tile) in terrain.get_bits().iter().enumerate() {
+            bytes[idx / 4] |= (terrain_from_bits(tile) as u8) << ((idx % 4) * 2);
+        }
+        PackedRoomTerrain { bytes }
+    }
+}
+
+impl From<LocalRoomTerrain> for PackedRoomTerrain {
+    fn from(terrain: LocalRoomTerrain) -> Self {
+        PackedRoomTerrain::from(&terrain)
+    }
+}
+
+impl From<&PackedRoomTerrain> for LocalRoomTerrain {
+    fn from(packed: &PackedRoomTerrain) -> Self {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for (idx, tile) in bits.iter_mut().enumerate() {
+            *tile = packed.get_bits(idx) & 0b11;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+}
+
+impl From<PackedRoomTerrain> for LocalRoomTerrain {
+    fn from(packed: PackedRoomTerrain) -> Self {
+        LocalRoomTerrain::from(&packed)
+    }
+}
+
+impl Serialize for PackedRoomTerrain {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_bytes(&self.bytes[..])
+    }
+}
+
+impl<'de> Deserialize<'de> for PackedRoomTerrain {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_bytes(PackedRoomTerrainVisitor)
+    }
+}
+
+struct PackedRoomTerrainVisitor;
+
+impl<'de> Visitor<'de> for PackedRoomTerrainVisitor {
+    type Value = PackedRoomTerrain;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{PACKED_TERRAIN_LEN} bytes of packed terrain")
+    }
+
+    fn visit_bytes<E>(self, bytes: &[u8]) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        let bytes: &[u8; PACKED_TERRAIN_LEN] = bytes
+            .try_into()
+            .map_err(|_| E::invalid_length(bytes.len(), &self))?;
+        Ok(PackedRoomTerrain::new_from_bytes(Box::new(*bytes)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = Box::new([0; PACKED_TERRAIN_LEN]);
+        for (idx, byte) in bytes.iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| de::Error::invalid_length(idx, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(de::Error::invalid_length(PACKED_TERRAIN_LEN + 1, &self));
+        }
+        Ok(PackedRoomTerrain::new_from_bytes(bytes))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::local::terrain_index_to_xy;
+
+    fn mixed_terrain() -> LocalRoomTerrain {
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for (idx, tile) in bits.iter_mut().enumerate() {
+            // includes the 0b11 wall+swamp value seen on some servers
+            *tile = (idx * 7 % 4) as u8;
+        }
+        LocalRoomTerrain::new_from_bits(bits)
+    }
+
+    #[test]
+    fn pack_round_trip() {
+        let terrain = mixed_terrain();
+        let packed = PackedRoomTerrain::from(&terrain);
+
+        for idx in 0..ROOM_AREA {
+            let xy = terrain_index_to_xy(idx);
+            assert_eq!(packed.get_xy(xy), terrain.get_xy(xy));
+        }
+        assert_eq!(LocalRoomTerrain::from(packed), terrain);
+    }
+
+    #[test]
+    fn round_trip_equality() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::{Hash, Hasher},
+        };
+
+        let hash = |terrain: &LocalRoomTerrain| {
+            let mut hasher = DefaultHasher::new();
+            terrain.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        // wall+swamp bytes, and bytes with bits above the lowest 2, which
+        // packing drops
+        let mut bits = Box::new([0; ROOM_AREA]);
+        bits[0] = 0b11;
+        bits[1] = 0b111;
+        bits[2] = 0b1000_0010;
+        let terrain = LocalRoomTerrain::new_from_bits(bits);
+        let round_trip = LocalRoomTerrain::from(PackedRoomTerrain::from(&terrain));
+        assert_ne!(round_trip.get_bits(), terrain.get_bits());
+        assert_eq!(round_trip, terrain);
+        assert_eq!(hash(&round_trip), hash(&terrain));
+
+        let mut walls = Box::new([0; ROOM_AREA]);
+        walls[0] = 0b01;
+        walls[1] = 0b01;
+        walls[2] = 0b10;
+        assert_eq!(LocalRoomTerrain::new_from_bits(walls), terrain);
+
+        let mut plain = Box::new([0; ROOM_AREA]);
+        plain[1] = 0b01;
+        plain[2] = 0b10;
+        assert_ne!(LocalRoomTerrain::new_from_bits(plain), terrain);
+    }
+
+    #[test]
+    fn packed_walls_are_equal() {
+        use std::{
+            collections::hash_map::DefaultHasher,
+            hash::{Hash, Hasher},
+        };
+
+        let hash = |packed: &PackedRoomTerrain| {
+            let mut hasher = DefaultHasher::new();
+            packed.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut wall_swamp = Box::new([0; ROOM_AREA]);
+        wall_swamp[0] = 0b11;
+        let mut wall = Box::new([0; ROOM_AREA]);
+        wall[0] = 0b01;
+        let wall_swamp = PackedRoomTerrain::from(LocalRoomTerrain::new_from_bits(wall_swamp));
+        let wall = PackedRoomTerrain::from(LocalRoomTerrain::new_from_bits(wall));
+        assert_eq!(wall_swamp, wall);
+        assert_eq!(hash(&wall_swamp), hash(&wall));
+        assert_eq!(wall_swamp.as_bytes()[0], 0b01);
+
+        // packed bytes given directly are stored the same way
+        let mut bytes = Box::new([0; PACKED_TERRAIN_LEN]);
+        bytes[0] = 0b10_11_01_11;
+        let packed = PackedRoomTerrain::new_from_bytes(bytes);
+        assert_eq!(packed.as_bytes()[0], 0b10_01_01_01);
+        assert_eq!(
+            packed.get_xy(RoomXY::checked_new(3, 0).unwrap()),
+            Terrain::Swamp
+        );
+    }
+
+    #[test]
+    fn serde() {
+        let terrain = mixed_terrain();
+
+        let binary = bincode::serialize(&terrain).unwrap();
+        assert!(binary.len() < PACKED_TERRAIN_LEN + 16);
+        assert_eq!(
+            bincode::deserialize::<LocalRoomTerrain>(&binary).unwrap(),
+            terrain
+        );
+
+        let json = serde_json::to_string(&terrain).unwrap();
+        assert_eq!(
+            serde_json::from_str::<LocalRoomTerrain>(&json).unwrap(),
+            terrain
+        );
+
+        let packed = PackedRoomTerrain::from(&terrain);
+        let json = serde_json::to_string(&packed).unwrap();
+        assert_eq!(
+            serde_json::from_str::<PackedRoomTerrain>(&json).unwrap(),
+            packed
+        );
+
+        assert!(serde_json::from_str::<LocalRoomTerrain>("[0, 1, 2]").is_err());
+        let too_long = serde_json::to_string(&vec![0; PACKED_TERRAIN_LEN + 1]).unwrap();
+        assert!(serde_json::from_str::<LocalRoomTerrain>(&too_long).is_err());
+    }
+}
@@ -0,0 +1,263 @@
+//! Conversion of room terrain to and from the text format used by the game
+//! server's database and map files.
+use std::{collections::HashMap, error::Error, fmt};
+
+use serde::Deserialize;
+
+use crate::{constants::ROOM_AREA, local::RoomName};
+
+use super::LocalRoomTerrain;
+
+impl LocalRoomTerrain {
+    /// Parses terrain from the string of 2500 digits used by the game server
+    /// to store each room's terrain, such as in the `terrain` field of rooms
+    /// in private server map files.
+    ///
+    /// Each digit is the terrain of one tile, in the same row-major order as
+    /// [`LocalRoomTerrain::new_from_bits`]: `0` for plains, `1` for walls,
+    /// `2` for swamps and `3` for tiles which are both walls and swamps, which
+    /// are treated as walls.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use screeps::{
+    ///     constants::Terrain,
+    ///     local::{LocalRoomTerrain, RoomXY},
+    /// };
+    ///
+    /// let text = format!("12{}", "0".repeat(2498));
+    /// let terrain = LocalRoomTerrain::from_terrain_string(&text).unwrap();
+    ///
+    /// assert_eq!(terrain.get_xy(RoomXY::checked_new(0, 0).unwrap()), Terrain::Wall);
+    /// assert_eq!(terrain.get_xy(RoomXY::checked_new(1, 0).unwrap()), Terrain::Swamp);
+    /// assert_eq!(terrain.to_terrain_string(), text);
+    /// ```
+    pub fn from_terrain_string(text: &str) -> Result<Self, TerrainParseError> {
+        if text.len() != ROOM_AREA {
+            return Err(TerrainParseError::InvalidLength(text.len()));
+        }
+
+        let mut bits = Box::new([0; ROOM_AREA]);
+        for ((index, character), tile) in text.char_indices().zip(bits.iter_mut()) {
+            *tile = match character {
+                '0'..='3' => character as u8 - b'0',
+                _ => return Err(TerrainParseError::InvalidCharacter { index, character }),
+            };
+        }
+
+        Ok(LocalRoomTerrain::new_from_bits(bits))
+    }
+
+    /// Converts terrain to the string of 2500 digits used by the game server,
+    /// as parsed by [`LocalRoomTerrain::from_terrain_string`].
+    pub fn to_terrain_string(&self) -> String {
+        self.get_bits()
+            .iter()
+            .map(|tile| char::from(b'0' + (tile & 0b11)))
+            .collect()
+    }
+}
+
+/// Parses the terrain of every room in a map file in the JSON format used by
+/// private servers, such as those exported from a server or provided by map
+/// tools.
+///
+/// The file may either be an object with a `rooms` array or an array of
+/// rooms, where each room is an object with a `room` name and a `terrain`
+/// string in the format parsed by [`LocalRoomTerrain::from_terrain_string`].
+/// Any other fields are ignored.
+///
+/// # Example
+///
+/// ```
+/// use screeps::local::{load_map_terrain, RoomName};
+///
+/// let json = format!(
+///     r#"{{"rooms": [{{"room": "W1N1", "terrain": "{}", "status": "normal"}}]}}"#,
+///     "1".repeat(2500)
+/// );
+/// let rooms = load_map_terrain(&json).unwrap();
+///
+/// assert_eq!(rooms.len(), 1);
+/// assert!(rooms.contains_key(&RoomName::new("W1N1").unwrap()));
+/// ```
+pub fn load_map_terrain(json: &str) -> Result<HashMap<RoomName, LocalRoomTerrain>, MapLoadError> {
+    #[derive(Deserialize)]
+    struct MapRoom {
+        room: RoomName,
+        terrain: String,
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum MapFile {
+        Object { rooms: Vec<MapRoom> },
+        Array(Vec<MapRoom>),
+    }
+
+    let rooms = match serde_json::from_str(json).map_err(MapLoadError::Json)? {
+        MapFile::Object { rooms } | MapFile::Array(rooms) => rooms,
+    };
+
+    rooms
+        .into_iter()
+        .map(|MapRoom { room, terrain }| {
+            LocalRoomTerrain::from_terrain_string(&terrain)
+                .map(|terrain| (room, terrain))
+                .map_err(|error| MapLoadError::Terrain { room, error })
+        })
+        .collect()
+}
+
+/// An error representing when a string can't be parsed into terrain.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TerrainParseError {
+    /// The string isn't 2500 characters long; contains the actual length in
+    /// bytes.
+    InvalidLength(usize),
+    /// A character of the string isn't a terrain digit from 0 to 3.
+    InvalidCharacter { index: usize, character: char },
+}
+
+impl Error for TerrainParseError {}
+
+impl fmt::Display for TerrainParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TerrainParseError::InvalidLength(len) => write!(
+                f,
+                "expected terrain string of {ROOM_AREA} digits, found length {len}"
+            ),
+            TerrainParseError::InvalidCharacter { index, character } => write!(
+                f,
+                "expected terrain digit 0-3, found `{character}` at index {index}"
+            ),
+        }
+    }
+}
+
+/// An error representing when a map file can't be loaded.
+#[derive(Debug)]
+pub enum MapLoadError {
+    /// The file isn't valid JSON in one of the expected formats.
+    Json(serde_json::Error),
+    /// The terrain of a room couldn't be parsed.
+    Terrain {
+        room: RoomName,
+        error: TerrainParseError,
+    },
+}
+
+impl Error for MapLoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MapLoadError::Json(error) => Some(error),
+            MapLoadError::Terrain { error, .. } => Some(error),
+        }
+    }
+}
+
+impl fmt::Display for MapLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MapLoadError::Json(error) => write!(f, "invalid map file: {error}"),
+            MapLoadError::Terrain { room, error } => {
+                write!(f, "invalid terrain for room {room}: {error}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{constants::Terrain, local::RoomXY};
+
+    #[test]
+    fn terrain_string_round_trip() {
+        let text: String = (0..ROOM_AREA)
+            .map(|idx| char::from(b'0' + (idx % 4) as u8))
+            .collect();
+        let terrain = LocalRoomTerrain::from_terrain_string(&text).unwrap();
+
+        // row-major, so the second row starts at index 50
+        assert_eq!(
+            terrain.get_xy(RoomXY::checked_new(0, 1).unwrap()),
+            Terrain::Swamp
+        );
+        assert_eq!(
+            terrain.get_xy(RoomXY::checked_new(3, 0).unwrap()),
+            Terrain::Wall
+        );
+        assert_eq!(terrain.to_terrain_string(), text);
+    }
+
+    #[test]
+    fn invalid_terrain_string() {
+        assert_eq!(
+            LocalRoomTerrain::from_terrain_string("0123").unwrap_err(),
+            TerrainParseError::InvalidLength(4)
+        );
+
+        let text = format!("{}4{}", "0".repeat(10), "0".repeat(2489));
+        assert_eq!(
+            LocalRoomTerrain::from_terrain_string(&text).unwrap_err(),
+            TerrainParseError::InvalidCharacter {
+                index: 10,
+                character: '4'
+            }
+        );
+
+        // multi-byte characters count by their length in bytes
+        let text = format!("é{}", "0".repeat(2498));
+        assert_eq!(
+            LocalRoomTerrain::from_terrain_string(&text).unwrap_err(),
+            TerrainParseError::InvalidCharacter {
+                index: 0,
+                character: 'é'
+            }
+        );
+    }
+
+    #[test]
+    fn load_map() {
+        let plains = "0".repeat(ROOM_AREA);
+        let walls = "1".repeat(ROOM_AREA);
+        let object = format!(
+            r#"{{"rooms": [
+                {{"room": "W0N0", "terrain": "{plains}", "objects": []}},
+                {{"room": "E0S0", "terrain": "{walls}", "status": "out of borders"}}
+            ]}}"#
+        );
+        let array = format!(
+            r#"[{{"room": "W0N0", "terrain": "{plains}"}}, {{"room": "E0S0", "terrain": "{walls}"}}]"#
+        );
+
+        for json in [object, array] {
+            let rooms = load_map_terrain(&json).unwrap();
+            assert_eq!(rooms.len(), 2);
+            let xy = RoomXY::checked_new(20, 20).unwrap();
+            assert_eq!(
+                rooms[&RoomName::new("W0N0").unwrap()].get_xy(xy),
+                Terrain::Plain
+            );
+            assert_eq!(
+                rooms[&RoomName::new("E0S0").unwrap()].get_xy(xy),
+                Terrain::Wall
+            );
+        }
+
+        assert!(matches!(
+            load_map_terrain(r#"{"rooms": [{"room": "W0N0", "terrain": "0"}]}"#),
+            Err(MapLoadError::Terrain {
+                error: TerrainParseError::InvalidLength(1),
+                ..
+            })
+        ));
+        assert!(matches!(
+            load_map_terrain(r#"{"rooms": [{"room": "nowhere", "terrain": ""}]}"#),
+            Err(MapLoadError::Json(_))
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+//! The effect of tower actions at range, with falloff and power effects.
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{
+    PowerType, TOWER_FALLOFF, TOWER_FALLOFF_RANGE, TOWER_OPTIMAL_RANGE, TOWER_POWER_ATTACK,
+    TOWER_POWER_HEAL, TOWER_POWER_REPAIR,
+};
+
+use super::{RoomMap, RoomXY};
+
+/// The multipliers of [`PowerType::OperateTower`] for each level of the
+/// power, from the `POWER_INFO` constant.
+const OPERATE_TOWER_EFFECT: [f64; 5] = [1.1, 1.2, 1.3, 1.4, 1.5];
+
+/// The multipliers of [`PowerType::DisruptTower`] for each level of the
+/// power, from the `POWER_INFO` constant.
+const DISRUPT_TOWER_EFFECT: [f64; 5] = [0.9, 0.8, 0.7, 0.6, 0.5];
+
+/// The power effects active on a tower which change the amount of its
+/// actions.
+///
+/// Each field is the level of the power which was used on the tower, from 1
+/// to 5, if its effect is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct TowerPowerEffects {
+    /// The level of [`PowerType::OperateTower`], increasing the tower's
+    /// output.
+    pub operate: Option<u8>,
+    /// The level of [`PowerType::DisruptTower`], decreasing the tower's
+    /// output.
+    pub disrupt: Option<u8>,
+}
+
+impl TowerPowerEffects {
+    /// Creates effects with neither power active.
+    #[inline]
+    pub const fn none() -> Self {
+        TowerPowerEffects {
+            operate: None,
+            disrupt: None,
+        }
+    }
+
+    /// Gets the level of the given power's effect, if it's one of the powers
+    /// affecting towers.
+    pub fn level(&self, power: PowerType) -> Option<u8> {
+        match power {
+            PowerType::OperateTower => self.operate,
+            PowerType::DisruptTower => self.disrupt,
+            _ => None,
+        }
+    }
+
+    fn apply(&self, mut amount: f64) -> f64 {
+        // like the game, operate applies before disrupt
+        for (level, effect) in [
+            (self.operate, &OPERATE_TOWER_EFFECT),
+            (self.disrupt, &DISRUPT_TOWER_EFFECT),
+        ] {
+            if let Some(level) = level {
+                amount *= effect[(level.clamp(1, 5) - 1) as usize];
+            }
+        }
+        amount
+    }
+}
+
+/// An action a tower can take on a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TowerAction {
+    /// Attacking a creep or structure, starting from [`TOWER_POWER_ATTACK`].
+    Attack,
+    /// Healing a creep, starting from [`TOWER_POWER_HEAL`].
+    Heal,
+    /// Repairing a structure, starting from [`TOWER_POWER_REPAIR`].
+    Repair,
+}
+
+impl TowerAction {
+    /// Gets the amount of the action at optimal range, without any power
+    /// effects.
+    #[inline]
+    pub const fn base_power(self) -> u32 {
+        match self {
+            TowerAction::Attack => TOWER_POWER_ATTACK,
+            TowerAction::Heal => TOWER_POWER_HEAL,
+            TowerAction::Repair => TOWER_POWER_REPAIR,
+        }
+    }
+
+    /// Gets the amount of the action on a target at the given range, with the
+    /// given power effects on the tower.
+    ///
+    /// The amount is reduced by up to [`TOWER_FALLOFF`] for targets between
+    /// [`TOWER_OPTIMAL_RANGE`] and [`TOWER_FALLOFF_RANGE`], then multiplied by
+    /// the power effects, then rounded down, in the same way as the game.
+    pub fn power(self, range: u8, effects: TowerPowerEffects) -> u32 {
+        let mut amount = self.base_power() as f64;
+        if range > TOWER_OPTIMAL_RANGE {
+            let range = range.min(TOWER_FALLOFF_RANGE);
+            amount -= amount * TOWER_FALLOFF * (range - TOWER_OPTIMAL_RANGE) as f64
+                / (TOWER_FALLOFF_RANGE - TOWER_OPTIMAL_RANGE) as f64;
+        }
+        effects.apply(amount).floor() as u32
+    }
+}
+
+/// Gets the damage a tower deals to a target at the given range, with the
+/// given power effects on the tower.
+///
+/// # Example
+///
+/// ```
+/// use screeps::local::{tower_attack_power, TowerPowerEffects};
+///
+/// assert_eq!(tower_attack_power(5, TowerPowerEffects::none()), 600);
+/// assert_eq!(tower_attack_power(10, TowerPowerEffects::none()), 450);
+/// assert_eq!(tower_attack_power(30, TowerPowerEffects::none()), 150);
+///
+/// let operated = TowerPowerEffects {
+///     operate: Some(5),
+///     disrupt: None,
+/// };
+/// assert_eq!(tower_attack_power(20, operated), 225);
+/// ```
+#[inline]
+pub fn tower_attack_power(range: u8, effects: TowerPowerEffects) -> u32 {
+    TowerAction::Attack.power(range, effects)
+}
+
+/// Gets the hits a tower heals on a creep at the given range, with the given
+/// power effects on the tower.
+#[inline]
+pub fn tower_heal_power(range: u8, effects: TowerPowerEffects) -> u32 {
+    TowerAction::Heal.power(range, effects)
+}
+
+/// Gets the hits a tower repairs on a structure at the given range, with the
+/// given power effects on the tower.
+#[inline]
+pub fn tower_repair_power(range: u8, effects: TowerPowerEffects) -> u32 {
+    TowerAction::Repair.power(range, effects)
+}
+
+/// Gets the total amount of an action from all of the given towers, if each
+/// acted on each tile of the room.
+///
+/// Each tower is given by its position in the room and the power effects on
+/// it.
+///
+/// # Example
+///
+/// ```
+/// use screeps::local::{tower_power_map, RoomXY, TowerAction, TowerPowerEffects};
+///
+/// let xy = |x, y| RoomXY::checked_new(x, y).unwrap();
+/// let towers = [
+///     (xy(10, 10), TowerPowerEffects::none()),
+///     (xy(40, 10), TowerPowerEffects::none()),
+/// ];
+/// let damage = tower_power_map(TowerAction::Attack, towers);
+///
+/// // range 5 from one tower and 25 from the other
+/// assert_eq!(damage[xy(15, 10)], 600 + 150);
+/// // range 15 from both
+/// assert_eq!(damage[xy(25, 10)], 300 + 300);
+/// ```
+pub fn tower_power_map(
+    action: TowerAction,
+    towers: impl IntoIterator<Item = (RoomXY, TowerPowerEffects)>,
+) -> RoomMap<u32> {
+    let mut map = RoomMap::new();
+    for (tower, effects) in towers {
+        // power only changes up to the falloff range, so compute each range
+        // once rather than for every tile
+        let by_range: Vec<u32> = (0..=TOWER_FALLOFF_RANGE)
+            .map(|range| action.power(range, effects))
+            .collect();
+        for (xy, total) in map.iter_mut() {
+            let range = xy.get_range_to(tower).min(TOWER_FALLOFF_RANGE);
+            *total += by_range[range as usize];
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn falloff() {
+        let none = TowerPowerEffects::none();
+        let attack: Vec<u32> = (0..=21)
+            .map(|range| tower_attack_power(range, none))
+            .collect();
+        assert_eq!(&attack[..=5], [600; 6]);
+        assert_eq!(
+            &attack[6..],
+            [570, 540, 510, 480, 450, 420, 390, 360, 330, 300, 270, 240, 210, 180, 150, 150]
+        );
+
+        assert_eq!(tower_heal_power(0, none), 400);
+        assert_eq!(tower_heal_power(6, none), 380);
+        assert_eq!(tower_heal_power(20, none), 100);
+        assert_eq!(tower_repair_power(3, none), 800);
+        assert_eq!(tower_repair_power(6, none), 760);
+        assert_eq!(tower_repair_power(u8::MAX, none), 200);
+    }
+
+    #[test]
+    fn power_effects() {
+        let effects = |operate, disrupt| TowerPowerEffects { operate, disrupt };
+
+        assert_eq!(tower_attack_power(0, effects(Some(1), None)), 660);
+        assert_eq!(tower_attack_power(0, effects(None, Some(5))), 300);
+        assert_eq!(tower_attack_power(0, effects(Some(1), Some(1))), 594);
+        // 570 * 1.1 * 0.8 rounds down from 501.6
+        assert_eq!(tower_attack_power(6, effects(Some(1), Some(2))), 501);
+        assert_eq!(tower_heal_power(6, effects(Some(3), None)), 494);
+        // levels outside 1 to 5 are clamped
+        assert_eq!(tower_heal_power(0, effects(Some(9), Some(0))), 540);
+
+        let operate = effects(Some(2), None);
+        assert_eq!(operate.level(PowerType::OperateTower), Some(2));
+        assert_eq!(operate.level(PowerType::DisruptTower), None);
+        assert_eq!(operate.level(PowerType::OperateSpawn), None);
+    }
+
+    #[test]
+    fn power_map() {
+        let towers = [
+            (
+                RoomXY::checked_new(0, 0).unwrap(),
+                TowerPowerEffects::none(),
+            ),
+            (
+                RoomXY::checked_new(49, 49).unwrap(),
+                TowerPowerEffects {
+                    operate: None,
+                    disrupt: Some(2),
+                },
+            ),
+        ];
+        let map = tower_power_map(TowerAction::Repair, towers);
+        for (xy, &total) in map.iter() {
+            let expected: u32 = towers
+                .iter()
+                .map(|&(tower, effects)| tower_repair_power(xy.get_range_to(tower), effects))
+                .sum();
+            assert_eq!(total, expected);
+        }
+        assert_eq!(
+            tower_power_map(TowerAction::Heal, []),
+            RoomMap::new_with_value(0)
+        );
+    }
+}
@@ -30,7 +30,7 @@ mod game_types {
 /// Object wrappers for simple javascript objects with known properties sent to
 /// game functions.
 pub mod input {
-    pub use super::impls::{FindPathOptions, JsFindPathOptions, MoveToOptions};
+    pub use super::impls::{FindPathOptions, JsFindPathOptions, MovePath, MoveToOptions};
 }
 
 /// Object wrappers for simple javascript objects with known properties returned
@@ -59,7 +59,7 @@ pub use self::{
     construction_site::ConstructionSite,
     cost_matrix::CostMatrix,
     creep::{BodyPart, Creep},
-    creep_shared::MoveToOptions,
+    creep_shared::{MovePath, MoveToOptions},
     deposit::Deposit,
     flag::Flag,
     mineral::Mineral,
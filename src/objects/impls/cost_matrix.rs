@@ -80,6 +80,24 @@ impl CostMatrix {
         matrix
     }
 
+    /// Get a [`Vec`] of numbers representing the [`CostMatrix`] in the same
+    /// format as [`CostMatrix::serialize`], without creating a JavaScript
+    /// array.
+    ///
+    /// See [`LocalCostMatrix::to_js_serialized`].
+    pub fn to_js_serialized(&self) -> Vec<u32> {
+        LocalCostMatrix::from(self).to_js_serialized()
+    }
+
+    /// Get a new [`CostMatrix`] from numbers in the format produced by
+    /// [`CostMatrix::serialize`], returning `None` if there aren't exactly 625
+    /// of them.
+    ///
+    /// See [`LocalCostMatrix::from_js_serialized`].
+    pub fn from_js_serialized(serialized: &[u32]) -> Option<CostMatrix> {
+        LocalCostMatrix::from_js_serialized(serialized).map(CostMatrix::from)
+    }
+
     // todo also a function that takes the unsafe view into wasm linear mem with
     // view for a matrix that'll easily go bad
 }
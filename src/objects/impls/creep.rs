@@ -8,7 +8,7 @@ use crate::{
     },
     pathfinder::SingleRoomCostResult,
     prelude::*,
-    CostMatrix, MoveToOptions, RoomName, RoomPosition,
+    CostMatrix, MovePath, MoveToOptions, RoomName, RoomPosition,
 };
 
 #[cfg(feature = "seasonal-season-5")]
@@ -399,11 +399,28 @@ impl Creep {
     }
 
     /// Move the creep along a previously determined path returned from a
-    /// pathfinding function, in array or serialized string form.
+    /// pathfinding function, in array or serialized string form, or along a
+    /// [`DirectionPath`].
+    ///
+    /// A [`DirectionPath`] is followed without passing it to JS, moving in the
+    /// direction given by [`DirectionPath::next_direction`], or returning
+    /// [`ErrorCode::NotFound`] if there's none.
     ///
     /// [Screeps documentation](https://docs.screeps.com/api/#Creep.moveByPath)
-    pub fn move_by_path(&self, path: &JsValue) -> Result<(), ErrorCode> {
-        ErrorCode::result_from_i8(self.move_by_path_internal(path))
+    ///
+    /// [`DirectionPath`]: crate::local::DirectionPath
+    /// [`DirectionPath::next_direction`]: crate::local::DirectionPath::next_direction
+    pub fn move_by_path<'a, P>(&self, path: P) -> Result<(), ErrorCode>
+    where
+        P: Into<MovePath<'a>>,
+    {
+        match path.into() {
+            MovePath::Js(path) => ErrorCode::result_from_i8(self.move_by_path_internal(path)),
+            MovePath::Directions(path) => match path.next_direction(self.pos()) {
+                Some(direction) => self.move_direction(direction),
+                None => Err(ErrorCode::NotFound),
+            },
+        }
     }
 
     /// Whether to send an email notification when this creep is attacked.
@@ -594,7 +611,10 @@ impl SharedCreepProperties for Creep {
         self.move_direction(direction)
     }
 
-    fn move_by_path(&self, path: &JsValue) -> Result<(), ErrorCode> {
+    fn move_by_path<'a, P>(&self, path: P) -> Result<(), ErrorCode>
+    where
+        P: Into<MovePath<'a>>,
+    {
         self.move_by_path(path)
     }
 
@@ -1,8 +1,8 @@
-use js_sys::Object;
+use js_sys::{Array, JsString, Object};
 use wasm_bindgen::prelude::*;
 
 use crate::{
-    local::RoomName,
+    local::{DirectionPath, RoomName},
     objects::{CostMatrix, FindPathOptions, PolyStyle},
     pathfinder::SingleRoomCostResult,
 };
@@ -42,6 +42,42 @@ impl Default for JsMoveToOptions {
     }
 }
 
+/// A path for a creep to follow with [`SharedCreepProperties::move_by_path`].
+///
+/// [`SharedCreepProperties::move_by_path`]: crate::SharedCreepProperties::move_by_path
+#[derive(Clone, Copy, Debug)]
+pub enum MovePath<'a> {
+    /// A path returned from a pathfinding function, in array or serialized
+    /// string form.
+    Js(&'a JsValue),
+    /// A [`DirectionPath`], followed without converting it to JS.
+    Directions(&'a DirectionPath),
+}
+
+impl<'a> From<&'a JsValue> for MovePath<'a> {
+    fn from(path: &'a JsValue) -> Self {
+        MovePath::Js(path)
+    }
+}
+
+impl<'a> From<&'a Array> for MovePath<'a> {
+    fn from(path: &'a Array) -> Self {
+        MovePath::Js(path.as_ref())
+    }
+}
+
+impl<'a> From<&'a JsString> for MovePath<'a> {
+    fn from(path: &'a JsString) -> Self {
+        MovePath::Js(path.as_ref())
+    }
+}
+
+impl<'a> From<&'a DirectionPath> for MovePath<'a> {
+    fn from(path: &'a DirectionPath) -> Self {
+        MovePath::Directions(path)
+    }
+}
+
 pub struct MoveToOptions<F>
 where
     F: FnMut(RoomName, CostMatrix) -> SingleRoomCostResult,
@@ -7,7 +7,7 @@ use crate::{
     constants::{Direction, ErrorCode, PowerCreepClass, PowerType, ResourceType},
     local::RoomName,
     objects::{
-        CostMatrix, MoveToOptions, Owner, Resource, RoomObject, RoomPosition, Store,
+        CostMatrix, MovePath, MoveToOptions, Owner, Resource, RoomObject, RoomPosition, Store,
         StructureController, StructurePowerSpawn,
     },
     pathfinder::SingleRoomCostResult,
@@ -265,11 +265,28 @@ impl PowerCreep {
     }
 
     /// Move the power creep along a previously determined path returned from a
-    /// pathfinding function, in array or serialized string form.
+    /// pathfinding function, in array or serialized string form, or along a
+    /// [`DirectionPath`].
+    ///
+    /// A [`DirectionPath`] is followed without passing it to JS, moving in the
+    /// direction given by [`DirectionPath::next_direction`], or returning
+    /// [`ErrorCode::NotFound`] if there's none.
     ///
     /// [Screeps documentation](https://docs.screeps.com/api/#PowerCreep.moveByPath)
-    pub fn move_by_path(&self, path: &JsValue) -> Result<(), ErrorCode> {
-        ErrorCode::result_from_i8(self.move_by_path_internal(path))
+    ///
+    /// [`DirectionPath`]: crate::local::DirectionPath
+    /// [`DirectionPath::next_direction`]: crate::local::DirectionPath::next_direction
+    pub fn move_by_path<'a, P>(&self, path: P) -> Result<(), ErrorCode>
+    where
+        P: Into<MovePath<'a>>,
+    {
+        match path.into() {
+            MovePath::Js(path) => ErrorCode::result_from_i8(self.move_by_path_internal(path)),
+            MovePath::Directions(path) => match path.next_direction(self.pos()) {
+                Some(direction) => self.move_direction(direction),
+                None => Err(ErrorCode::NotFound),
+            },
+        }
     }
 
     /// Whether to send an email notification when this power creep is attacked.
@@ -395,7 +412,10 @@ impl SharedCreepProperties for PowerCreep {
         self.move_direction(direction)
     }
 
-    fn move_by_path(&self, path: &JsValue) -> Result<(), ErrorCode> {
+    fn move_by_path<'a, P>(&self, path: P) -> Result<(), ErrorCode>
+    where
+        P: Into<MovePath<'a>>,
+    {
         self.move_by_path(path)
     }
 
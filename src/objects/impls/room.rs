@@ -14,7 +14,7 @@ use crate::{
         find::*, look::*, Color, Direction, ErrorCode, ExitDirection, PowerType, ResourceType,
         StructureType,
     },
-    local::{LodashFilter, RoomName},
+    local::{self, LodashFilter, RoomName},
     objects::*,
     pathfinder::RoomCostResult,
     prelude::*,
@@ -77,12 +77,6 @@ extern "C" {
     #[wasm_bindgen(method, getter)]
     pub fn terminal(this: &Room) -> Option<StructureTerminal>;
 
-    #[wasm_bindgen(static_method_of = Room, js_name = serializePath)]
-    fn serialize_path_internal(path: &Array) -> JsString;
-
-    #[wasm_bindgen(static_method_of = Room, js_name = deserializePath)]
-    fn deserialize_path_internal(path: &JsString) -> Array;
-
     #[wasm_bindgen(final, method, js_name = createConstructionSite)]
     fn create_construction_site_internal(
         this: &Room,
@@ -182,25 +176,23 @@ impl Room {
     /// Serialize a path array from [`Room::find_path`] into a string
     /// representation safe to store in memory.
     ///
+    /// This is done in Rust with [`local::serialize_path`], which produces the
+    /// same string as the game's implementation.
+    ///
     /// [Screeps documentation](https://docs.screeps.com/api/#Room.serializePath)
     pub fn serialize_path(path: &[Step]) -> String {
-        Self::serialize_path_internal(
-            serde_wasm_bindgen::to_value(path)
-                .expect("invalid path passed to serialize")
-                .unchecked_ref(),
-        )
-        .into()
+        local::serialize_path(path)
     }
 
     /// Deserialize a string representation from [`Room::serialize_path`] back
     /// to a path array.
     ///
+    /// This is done in Rust with [`local::deserialize_path`]; use that
+    /// function directly to handle invalid strings without panicking.
+    ///
     /// [Screeps documentation](https://docs.screeps.com/api/#Room.deserializePath)
     pub fn deserialize_path(path: &str) -> Vec<Step> {
-        serde_wasm_bindgen::from_value(
-            Self::deserialize_path_internal(&JsString::from(path)).unchecked_into(),
-        )
-        .expect("invalid deserialized path")
+        local::deserialize_path(path).expect("invalid deserialized path")
     }
 
     pub fn visual(&self) -> RoomVisual {
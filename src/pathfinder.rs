@@ -319,8 +319,8 @@ where
 
 #[wasm_bindgen]
 pub struct SearchGoal {
-    pos: Position,
-    range: u32,
+    pub(crate) pos: Position,
+    pub(crate) range: u32,
 }
 
 impl SearchGoal {
@@ -236,8 +236,13 @@ pub trait SharedCreepProperties {
     fn move_direction(&self, direction: Direction) -> Result<(), ErrorCode>;
 
     /// Move the creep along a previously determined path returned from a
-    /// pathfinding function, in array or serialized string form.
-    fn move_by_path(&self, path: &JsValue) -> Result<(), ErrorCode>;
+    /// pathfinding function, in array or serialized string form, or along a
+    /// [`DirectionPath`].
+    ///
+    /// [`DirectionPath`]: crate::local::DirectionPath
+    fn move_by_path<'a, P>(&self, path: P) -> Result<(), ErrorCode>
+    where
+        P: Into<MovePath<'a>>;
 
     /// Move the creep toward the specified goal, either a [`RoomPosition`] or
     /// [`RoomObject`]. Note that using this function will store data in